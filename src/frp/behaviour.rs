@@ -1,5 +1,33 @@
-use crate::frp::{Error, Event, InputSet, OutputSet, TypedOutHandle};
-use std::{cell::RefCell, rc::Rc};
+use crate::frp::{Error, Event, InHandle, InputSet, InputSetId, OutHandle, OutputSet, OutputSetId, TypedOutHandle};
+use std::{any, cell::RefCell, error, rc::Rc};
+
+/// Error returned by a fallible behaviour.
+pub type BehaviourError = Box<dyn error::Error>;
+
+/// Condition of calling a behaviour when its inputs have changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum FiringPolicy {
+    /// Fire on any change of the inputs.
+    #[default]
+    Any,
+    /// Fire on any change of the inputs, but only when all the inputs hold a value.
+    AllReady,
+    /// Fire only when any of the given inputs has changed, the changes of the other inputs are sampled
+    /// when the behaviour fires. The node cannot be created if any of the pins is missing.
+    Triggers(Vec<usize>),
+}
+
+impl FiringPolicy {
+    /// Returns if a behaviour with the given inputs shall fire.
+    pub fn is_satisfied(&self, input_set: &dyn InputSet) -> bool {
+        input_set.is_dirty()
+            && match self {
+                FiringPolicy::Any => true,
+                FiringPolicy::AllReady => input_set.has_values(),
+                FiringPolicy::Triggers(pins) => pins.iter().any(|&pin| input_set.changed(pin)),
+            }
+    }
+}
 
 /// Implements the core logic to consume input and generate output
 pub trait Behaviour: 'static {
@@ -7,8 +35,30 @@ pub trait Behaviour: 'static {
     type OutputSet: OutputSet;
     type PinLayout;
 
-    /// Perform the logic to consume inputs and trigger the outputs.
-    fn behave(&mut self, input_set: &mut Self::InputSet, output_set: &mut Self::OutputSet);
+    /// Perform the logic to consume inputs and trigger the outputs. Either this or `try_behave` has to be
+    /// implemented, by default it calls `try_behave` and logs the failure.
+    fn behave(&mut self, input_set: &mut Self::InputSet, output_set: &mut Self::OutputSet) {
+        if let Err(err) = self.try_behave(input_set, output_set) {
+            log::error!("Behaviour {} failed: {}", any::type_name::<Self>(), err);
+        }
+    }
+
+    /// Fallible version of `behave`, this is the function called by the system. The failure is handled by
+    /// the `ErrorPolicy` of the system. By default it calls `behave` and never fails.
+    fn try_behave(
+        &mut self,
+        input_set: &mut Self::InputSet,
+        output_set: &mut Self::OutputSet,
+    ) -> Result<(), BehaviourError> {
+        self.behave(input_set, output_set);
+        Ok(())
+    }
+
+    /// Return the condition of calling the behaviour, it is queried once when the node is created.
+    /// By default the behaviour fires on any change of the inputs.
+    fn firing_policy(&self) -> FiringPolicy {
+        FiringPolicy::Any
+    }
 
     /// Return int input/output pin layout for clients to connect behaviour into graph.
     fn get_pins(
@@ -23,15 +73,33 @@ pub struct BehaviourNode<B: Behaviour> {
     pub(in crate::frp) input_set: Rc<RefCell<<B as Behaviour>::InputSet>>,
     pub(in crate::frp) output_set: Rc<RefCell<<B as Behaviour>::OutputSet>>,
     behaviour: B,
+    firing_policy: FiringPolicy,
 }
 
 impl<B: Behaviour> BehaviourNode<B> {
-    pub fn new(input_set: <B as Behaviour>::InputSet, output_set: <B as Behaviour>::OutputSet, behaviour: B) -> Self {
-        Self {
+    /// Create a node, the pins of the firing policy have to be present in the input set.
+    pub fn new(
+        input_set: <B as Behaviour>::InputSet,
+        output_set: <B as Behaviour>::OutputSet,
+        behaviour: B,
+    ) -> Result<Self, Error> {
+        let firing_policy = behaviour.firing_policy();
+        if let FiringPolicy::Triggers(triggers) = &firing_policy {
+            let pins = input_set.pins();
+            if let Some(&index) = triggers.iter().find(|&&id| !pins.iter().any(|pin| pin.pin_id == id)) {
+                return Err(Error::PinNotFound {
+                    set: input_set.id().into(),
+                    index,
+                });
+            }
+        }
+
+        Ok(Self {
             input_set: Rc::new(RefCell::new(input_set)),
             output_set: Rc::new(RefCell::new(output_set)),
+            firing_policy,
             behaviour,
-        }
+        })
     }
 
     pub fn get_pins(&self) -> <B as Behaviour>::PinLayout {
@@ -47,22 +115,90 @@ pub trait IntoBehaviourNode {
 
 /// Type erased `BehaviourNode`.
 pub(in crate::frp) trait GeneralBehaviourNode {
-    fn process(&mut self);
+    /// Name of the type of the behaviour.
+    fn name(&self) -> &'static str;
+
+    fn input_set_id(&self) -> InputSetId;
+    fn output_set_id(&self) -> OutputSetId;
+
+    /// Create a handle to an input pin, if there is no pin with the given id None is returned.
+    fn input_handle(&self, pin_id: usize) -> Option<InHandle>;
+
+    /// Create a handle to an output pin, if there is no pin with the given id None is returned.
+    fn output_handle(&self, pin_id: usize) -> Option<OutHandle>;
+
+    /// Returns if the node has unprocessed events that satisfy the firing policy.
+    fn is_dirty(&self) -> bool;
+
+    /// Call the behaviour if the node has unprocessed events that satisfy the firing policy and return if the
+    /// behaviour was called. The changes not satisfying the policy are kept until the behaviour fires.
+    fn process(&mut self) -> Result<bool, BehaviourError>;
 }
 
 impl<B> GeneralBehaviourNode for BehaviourNode<B>
 where
     B: Behaviour,
 {
-    fn process(&mut self) {
+    fn name(&self) -> &'static str {
+        any::type_name::<B>()
+    }
+
+    fn input_set_id(&self) -> InputSetId {
+        self.input_set.borrow().id()
+    }
+
+    fn output_set_id(&self) -> OutputSetId {
+        self.output_set.borrow().id()
+    }
+
+    fn input_handle(&self, pin_id: usize) -> Option<InHandle> {
+        let pin = self
+            .input_set
+            .borrow()
+            .pins()
+            .into_iter()
+            .find(|pin| pin.pin_id == pin_id)?;
+        Some(InHandle::new(
+            &self.input_set,
+            pin_id,
+            pin.event_type_id,
+            pin.event_type_name,
+        ))
+    }
+
+    fn output_handle(&self, pin_id: usize) -> Option<OutHandle> {
+        let pin = self
+            .output_set
+            .borrow()
+            .pins()
+            .into_iter()
+            .find(|pin| pin.pin_id == pin_id)?;
+        Some(OutHandle::new(
+            &self.output_set,
+            pin_id,
+            pin.event_type_id,
+            pin.event_type_name,
+        ))
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.firing_policy.is_satisfied(&*self.input_set.borrow())
+    }
+
+    fn process(&mut self) -> Result<bool, BehaviourError> {
         // The input and output are borrowed for the entire process,
         // but since graph shall contain no cycle and hence no output shall
         // trigger the already borrowed input.
         let input = &mut *self.input_set.borrow_mut();
         let output = &mut *self.output_set.borrow_mut();
-        if input.is_dirty() {
+        if self.firing_policy.is_satisfied(input) {
+            // the changes are cleared after the call, so the behaviour can query the changed inputs
+            let result = self.behaviour.try_behave(input, output);
             input.reset_dirty();
-            self.behaviour.behave(input, output);
+            result?;
+            Ok(true)
+        } else {
+            Ok(false)
         }
     }
 }
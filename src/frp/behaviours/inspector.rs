@@ -40,6 +40,6 @@ impl<T: Event + Debug> IntoBehaviourNode for Inspector<T> {
     fn into_behaviour_node(self) -> Result<BehaviourNode<Self::Behaviour>, Error> {
         let input_set = FixedInputSet::default();
         let output_set = FixedOutSet::default();
-        Ok(BehaviourNode::new(input_set, output_set, self))
+        BehaviourNode::new(input_set, output_set, self)
     }
 }
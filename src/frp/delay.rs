@@ -0,0 +1,114 @@
+use crate::frp::{
+    downcast, Error, Event, InHandle, InPin, InputSet, InputSetId, OutHandle, PinTracer, SetOwner, TraceSink,
+    TypedInHandle,
+};
+use std::any::{self, Any, TypeId};
+
+/// Input set of a delayed connection. The events are stored until the next run of the system, when they
+/// are forwarded to the connected input. As the forwarding happens out of the regular flow of the events,
+/// delayed connections are not part of the topology and can be used to close cycles.
+pub(in crate::frp) struct DelayBuffer<T: Event> {
+    id: InputSetId,
+    owner: SetOwner,
+    source: OutHandle,
+    target: TypedInHandle<T>,
+    events: Vec<T>,
+    tracer: Option<PinTracer>,
+}
+
+impl<T: Event> DelayBuffer<T> {
+    pub fn new(source: OutHandle, target: TypedInHandle<T>) -> Self {
+        Self {
+            id: InputSetId::new(),
+            owner: SetOwner::default(),
+            source,
+            target,
+            events: Vec::new(),
+            tracer: None,
+        }
+    }
+}
+
+impl<T: Event> InputSet for DelayBuffer<T> {
+    fn id(&self) -> InputSetId {
+        self.id
+    }
+
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<InPin> {
+        vec![InPin {
+            pin_id: 0,
+            event_type_id: TypeId::of::<T>(),
+            event_type_name: any::type_name::<T>(),
+        }]
+    }
+
+    fn push(&mut self, id: usize, event: &dyn Any) -> Result<(), Error> {
+        match id {
+            0 => self.events.push(downcast::<T>(event, self.id.into(), id)?.clone()),
+            _ => {
+                return Err(Error::PinNotFound {
+                    set: self.id.into(),
+                    index: id,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    fn is_dirty(&self) -> bool {
+        !self.events.is_empty()
+    }
+
+    fn reset_dirty(&mut self) {}
+}
+
+/// Type erased `DelayBuffer`.
+pub(in crate::frp) trait DelayedConnection {
+    /// Id of the input set the source output is connected to.
+    fn id(&self) -> InputSetId;
+
+    /// The output the events are received from.
+    fn source(&self) -> &OutHandle;
+
+    /// The input the events are forwarded to.
+    fn target(&self) -> &InHandle;
+
+    /// Forward the stored events to the connected input and return the number of the forwarded events.
+    fn flush(&mut self) -> usize;
+
+    /// Install or remove the tracing of the forwarded events, they are traced as deliveries from the source.
+    fn set_tracer(&mut self, sink: Option<&TraceSink>);
+}
+
+impl<T: Event> DelayedConnection for DelayBuffer<T> {
+    fn id(&self) -> InputSetId {
+        self.id
+    }
+
+    fn source(&self) -> &OutHandle {
+        &self.source
+    }
+
+    fn target(&self) -> &InHandle {
+        self.target.handle()
+    }
+
+    fn flush(&mut self) -> usize {
+        let forwarded = self.events.len();
+        for event in self.events.drain(..) {
+            if let Some(tracer) = &self.tracer {
+                tracer.trace(self.target.handle(), &event);
+            }
+            self.target.push(&event);
+        }
+        forwarded
+    }
+
+    fn set_tracer(&mut self, sink: Option<&TraceSink>) {
+        self.tracer = sink.map(|sink| PinTracer::new(sink, self.source.set_id(), self.source.pin_id()));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::frp::Error;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// Serializable description of a system. The behaviours and event types are referenced by the names they
+/// were registered with in a `BehaviourRegistry`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SystemDescription {
+    #[serde(default)]
+    pub inputs: Vec<PinDescription>,
+    #[serde(default)]
+    pub outputs: Vec<PinDescription>,
+    #[serde(default)]
+    pub behaviours: Vec<BehaviourDescription>,
+    #[serde(default)]
+    pub connections: Vec<ConnectionDescription>,
+}
+
+/// Named input or output of the system.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PinDescription {
+    pub name: String,
+    /// Registered name of the event type
+    pub event_type: String,
+}
+
+/// Named instance of a behaviour.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BehaviourDescription {
+    pub name: String,
+    /// Registered name of the behaviour
+    pub behaviour: String,
+    /// Parameters to create the behaviour from
+    #[serde(default = "default_parameters")]
+    pub parameters: ron::Value,
+}
+
+fn default_parameters() -> ron::Value {
+    ron::Value::Unit
+}
+
+/// Reference to a pin by the names in the description.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PinReference {
+    /// Input of the system when used as a source, output of the system when used as a target.
+    System(String),
+    /// Pin of a behaviour given by the name of the behaviour and the pin id.
+    Behaviour(String, usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionDescription {
+    pub from: PinReference,
+    pub to: PinReference,
+    #[serde(default)]
+    pub delayed: bool,
+}
+
+impl SystemDescription {
+    /// Parse a description from RON.
+    pub fn from_ron(source: &str) -> Result<Self, Error> {
+        ron::from_str(source).map_err(|err| Error::InvalidDescription(err.to_string()))
+    }
+
+    /// Format the description as RON.
+    pub fn to_ron(&self) -> Result<String, Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| Error::InvalidDescription(err.to_string()))
+    }
+
+    /// Read a RON description from a file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+
+    /// Write the description as RON into a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+}
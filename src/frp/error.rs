@@ -1,3 +1,5 @@
+use crate::frp::{InputSetId, NodeId, OutputSetId, TypedInHandle};
+use std::fmt;
 use thiserror::Error as ThisError;
 
 #[derive(Debug, ThisError)]
@@ -9,8 +11,120 @@ pub enum Error {
     InputNotFound,
     #[error("Output was not found")]
     OutputNotFound,
-    #[error("Unexpected event type")]
-    UnexpectedEventType,
-    #[error("The event type if input and output are not the matching")]
-    IncompatiblePinTypes,
+    #[error("Node was not found")]
+    NodeNotFound,
+    #[error("Connection was not found")]
+    ConnectionNotFound,
+    #[error("Unexpected event type for {pin}")]
+    UnexpectedEventType { pin: PinInfo },
+    #[error("The event type of the output {output} does not match the input {input}")]
+    IncompatiblePinTypes { output: PinInfo, input: PinInfo },
+    #[error("The node or pin belongs to another system")]
+    ForeignHandle,
+    #[error("Pin {index} was not found in {set:?}")]
+    PinNotFound { set: SetId, index: usize },
+    #[error("Type mismatch, expected {expected} but found {found}")]
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+
+    #[error("Behaviour is not registered: {0}")]
+    UnknownBehaviour(String),
+    #[error("Event type is not registered: {0}")]
+    UnknownEventType(String),
+    #[error("Name or event type is already registered: {0}")]
+    DuplicateRegistration(String),
+    #[error("Name was not found: {0}")]
+    NameNotFound(String),
+    #[error("Invalid description: {0}")]
+    InvalidDescription(String),
+    #[error("Failed to access the description")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Behaviour(NodeError),
+}
+
+/// Id of an input or output set.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SetId {
+    Input(InputSetId),
+    Output(OutputSetId),
+}
+
+impl From<InputSetId> for SetId {
+    fn from(id: InputSetId) -> Self {
+        SetId::Input(id)
+    }
+}
+
+impl From<OutputSetId> for SetId {
+    fn from(id: OutputSetId) -> Self {
+        SetId::Output(id)
+    }
+}
+
+/// Identity and event type of a pin.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PinInfo {
+    pub set: SetId,
+    pub pin_id: usize,
+    pub event_type_name: &'static str,
+}
+
+impl fmt::Display for PinInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pin {} of {:?} ({})", self.pin_id, self.set, self.event_type_name)
+    }
+}
+
+/// Failure of a behaviour with the identity of the node.
+#[derive(Clone, Debug)]
+pub struct NodeError {
+    pub node: NodeId,
+    /// Name of the type of the behaviour
+    pub name: String,
+    /// Message of the error returned by the behaviour
+    pub message: String,
+}
+
+impl fmt::Display for NodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Behaviour {} ({:?}) failed: {}", self.name, self.node, self.message)
+    }
+}
+
+/// Panic of a behaviour with the identity of the quarantined node.
+#[derive(Clone, Debug)]
+pub struct NodePanic {
+    pub node: NodeId,
+    /// Name of the type of the behaviour
+    pub name: String,
+    /// Message of the panic if it was a string
+    pub message: Option<String>,
+}
+
+impl fmt::Display for NodePanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Behaviour {} ({:?}) panicked", self.name, self.node)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Handling of the failed behaviours during a run.
+#[derive(Clone, Default)]
+pub enum ErrorPolicy {
+    /// Stop the run and return the error. The nodes waiting for execution are processed in the next run
+    /// they are affected by.
+    #[default]
+    Abort,
+    /// Log the error and continue the run. The events sent by the failed node before the failure are delivered.
+    Skip,
+    /// Send the error to an input (ex. an output of the system) and continue the run. If the input belongs to
+    /// the failed node or to a node preceding it in the execution order, the error is delivered in the next run.
+    Route(TypedInHandle<NodeError>),
 }
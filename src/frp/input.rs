@@ -1,6 +1,7 @@
-use crate::frp::{next_id, Event};
+use crate::frp::{next_id, Error, Event, PinInfo, SetId, SetOwner, SystemId};
+use downcast_rs::{impl_downcast, Downcast};
 use std::{
-    any::{Any, TypeId},
+    any::{self, Any, TypeId},
     cell::RefCell,
     marker::PhantomData,
     ops::{Deref, DerefMut},
@@ -11,28 +12,60 @@ use std::{
 pub trait In: 'static {
     type Event: Event;
 
+    /// Store an event and return if the input was changed by it.
     fn push(&mut self, event: &Self::Event) -> bool;
+
+    /// Returns if the input holds a value, by default an input is always ready.
+    fn has_value(&self) -> bool {
+        true
+    }
 }
 
 /// Type erased version of an `In`
-pub(in crate::frp) trait GeneralIn {
-    /// Get the type of the produced event
+pub(in crate::frp) trait GeneralIn: Downcast {
+    /// Get the type of the received event
     fn event_type_id(&self) -> TypeId;
 
-    /// Store a type erased event.
-    /// #Panic
-    /// This function may panic if the type cannot be downcasted to the type of the input.
-    fn push_any(&mut self, event: &dyn Any) -> bool;
+    /// Get the name of the type of the received event
+    fn event_type_name(&self) -> &'static str;
+
+    /// Store a type erased event received by the given pin. If the type cannot be downcasted to the type of the
+    /// input, an error is returned.
+    fn push_any(&mut self, set: SetId, pin_id: usize, event: &dyn Any) -> Result<bool, Error>;
+
+    /// Returns if the input holds a value.
+    fn has_value_any(&self) -> bool;
 }
 
+impl_downcast!(GeneralIn);
+
 impl<T: In> GeneralIn for T {
     fn event_type_id(&self) -> TypeId {
-        TypeId::of::<T>()
+        TypeId::of::<T::Event>()
+    }
+
+    fn event_type_name(&self) -> &'static str {
+        any::type_name::<T::Event>()
     }
 
-    fn push_any(&mut self, event: &dyn Any) -> bool {
-        self.push(event.downcast_ref::<T::Event>().unwrap())
+    fn push_any(&mut self, set: SetId, pin_id: usize, event: &dyn Any) -> Result<bool, Error> {
+        Ok(self.push(downcast::<T::Event>(event, set, pin_id)?))
     }
+
+    fn has_value_any(&self) -> bool {
+        self.has_value()
+    }
+}
+
+/// Downcast a type erased event to the event type of a pin.
+pub(in crate::frp) fn downcast<T: Event>(event: &dyn Any, set: SetId, pin_id: usize) -> Result<&T, Error> {
+    event.downcast_ref::<T>().ok_or_else(|| Error::UnexpectedEventType {
+        pin: PinInfo {
+            set,
+            pin_id,
+            event_type_name: any::type_name::<T>(),
+        },
+    })
 }
 
 /// Unique id of an output set.
@@ -46,19 +79,41 @@ impl InputSetId {
     }
 }
 
+/// Description of an input pin for the inspection of the graph.
+pub struct InPin {
+    pub pin_id: usize,
+    pub event_type_id: TypeId,
+    pub event_type_name: &'static str,
+}
+
 /// The input set of a `Behaviour`.
 pub trait InputSet: 'static {
     fn id(&self) -> InputSetId;
 
-    /// Send an event to the input with the given id.
-    /// #Panic
-    /// This function may panic if either the index of the input is invalid or the type
-    /// cannot be downcasted to the type of the input.
-    fn push(&mut self, id: usize, event: &dyn Any);
+    /// The system the set was added to.
+    fn owner(&self) -> &SetOwner;
+
+    /// Describe the input pins of the set.
+    fn pins(&self) -> Vec<InPin>;
+
+    /// Send an event to the input with the given id. If either the index of the input is invalid or the type
+    /// cannot be downcasted to the type of the input, an error is returned.
+    fn push(&mut self, id: usize, event: &dyn Any) -> Result<(), Error>;
 
     /// Returns if event were submitted since the reset.
     fn is_dirty(&self) -> bool;
 
+    /// Returns if all the inputs hold a value, by default the set is always ready.
+    fn has_values(&self) -> bool {
+        true
+    }
+
+    /// Returns if the input with the given id received a change since the reset.
+    /// By default any change of the set is reported for all the inputs.
+    fn changed(&self, _id: usize) -> bool {
+        self.is_dirty()
+    }
+
     /// Clears the dirty flag.
     fn reset_dirty(&mut self);
 }
@@ -66,16 +121,19 @@ pub trait InputSet: 'static {
 /// Dynamic set of inputs constructed programmatically.
 pub struct DynamicInputSet {
     id: InputSetId,
+    owner: SetOwner,
     inputs: Vec<Box<dyn GeneralIn>>,
-    dirty: bool,
+    /// The inputs that received a change since the reset
+    changed: Vec<bool>,
 }
 
 impl Default for DynamicInputSet {
     fn default() -> Self {
         Self {
             id: InputSetId::new(),
+            owner: SetOwner::default(),
             inputs: Vec::new(),
-            dirty: false,
+            changed: Vec::new(),
         }
     }
 }
@@ -84,8 +142,38 @@ impl DynamicInputSet {
     pub fn add<I: In>(&mut self, input: I) -> usize {
         let id = self.inputs.len();
         self.inputs.push(Box::new(input));
+        self.changed.push(false);
         id
     }
+
+    /// Get the input with the given id if it is an `I`.
+    pub fn get<I: In>(&self, id: usize) -> Option<&I> {
+        self.inputs.get(id).and_then(|input| (**input).downcast_ref::<I>())
+    }
+
+    /// Get the input with the given id mutably if it is an `I`.
+    pub fn get_mut<I: In>(&mut self, id: usize) -> Option<&mut I> {
+        self.inputs.get_mut(id).and_then(|input| (**input).downcast_mut::<I>())
+    }
+
+    /// Create a handle to the input with the given id. If there is no such input or its event type is not `T`,
+    /// an error is returned.
+    pub fn handle<T: Event>(input_set: &Rc<RefCell<Self>>, id: usize) -> Result<TypedInHandle<T>, Error> {
+        {
+            let this = input_set.borrow();
+            let input = this.inputs.get(id).ok_or(Error::PinNotFound {
+                set: this.id.into(),
+                index: id,
+            })?;
+            if input.event_type_id() != TypeId::of::<T>() {
+                return Err(Error::TypeMismatch {
+                    expected: any::type_name::<T>(),
+                    found: input.event_type_name(),
+                });
+            }
+        }
+        Ok(TypedInHandle::new(input_set, id))
+    }
 }
 
 impl InputSet for DynamicInputSet {
@@ -93,32 +181,64 @@ impl InputSet for DynamicInputSet {
         self.id
     }
 
-    fn push(&mut self, id: usize, event: &dyn Any) {
-        self.dirty |= self.inputs[id].push_any(event);
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<InPin> {
+        self.inputs
+            .iter()
+            .enumerate()
+            .map(|(pin_id, input)| InPin {
+                pin_id,
+                event_type_id: input.event_type_id(),
+                event_type_name: input.event_type_name(),
+            })
+            .collect()
+    }
+
+    fn push(&mut self, id: usize, event: &dyn Any) -> Result<(), Error> {
+        let input = self.inputs.get_mut(id).ok_or(Error::PinNotFound {
+            set: self.id.into(),
+            index: id,
+        })?;
+        self.changed[id] |= input.push_any(self.id.into(), id, event)?;
+        Ok(())
     }
 
     fn is_dirty(&self) -> bool {
-        self.dirty
+        self.changed.iter().any(|&changed| changed)
+    }
+
+    fn has_values(&self) -> bool {
+        self.inputs.iter().all(|input| input.has_value_any())
+    }
+
+    fn changed(&self, id: usize) -> bool {
+        self.changed.get(id).copied().unwrap_or(false)
     }
 
     fn reset_dirty(&mut self) {
-        self.dirty = false;
+        self.changed.fill(false);
     }
 }
 
 /// Static, compile time definition of a set of inputs.
 pub struct FixedInputSet<I> {
     id: InputSetId,
+    owner: SetOwner,
     inputs: I,
-    dirty: bool,
+    /// Bitmask of the inputs that received a change since the reset
+    changed: u64,
 }
 
 impl<I: Default> Default for FixedInputSet<I> {
     fn default() -> Self {
         Self {
             id: InputSetId::new(),
+            owner: SetOwner::default(),
             inputs: I::default(),
-            dirty: false,
+            changed: 0,
         }
     }
 }
@@ -127,10 +247,21 @@ impl<I> FixedInputSet<I> {
     pub fn new(input: I) -> Self {
         Self {
             id: InputSetId::new(),
+            owner: SetOwner::default(),
             inputs: input,
-            dirty: false,
+            changed: 0,
         }
     }
+
+    /// Get the bitmask of the inputs that received a change since the last call of the behaviour,
+    /// the bit `i` is set for the input with id `i`.
+    pub fn changed_mask(&self) -> u64 {
+        self.changed
+    }
+
+    fn is_changed(&self, id: usize) -> bool {
+        id < 64 && self.changed & (1 << id) != 0
+    }
 }
 
 impl<I> Deref for FixedInputSet<I> {
@@ -152,19 +283,46 @@ impl<I1: In> InputSet for FixedInputSet<I1> {
         self.id
     }
 
-    fn push(&mut self, id: usize, event: &dyn Any) {
-        match id {
-            0 => self.dirty |= self.inputs.push(event.downcast_ref::<I1::Event>().unwrap()),
-            _ => unreachable!(),
-        }
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<InPin> {
+        vec![InPin {
+            pin_id: 0,
+            event_type_id: TypeId::of::<I1::Event>(),
+            event_type_name: any::type_name::<I1::Event>(),
+        }]
+    }
+
+    fn push(&mut self, id: usize, event: &dyn Any) -> Result<(), Error> {
+        let changed = match id {
+            0 => self.inputs.push(downcast::<I1::Event>(event, self.id.into(), id)?),
+            _ => {
+                return Err(Error::PinNotFound {
+                    set: self.id.into(),
+                    index: id,
+                })
+            }
+        };
+        self.changed |= (changed as u64) << id;
+        Ok(())
     }
 
     fn is_dirty(&self) -> bool {
-        self.dirty
+        self.changed != 0
+    }
+
+    fn has_values(&self) -> bool {
+        self.inputs.has_value()
+    }
+
+    fn changed(&self, id: usize) -> bool {
+        self.is_changed(id)
     }
 
     fn reset_dirty(&mut self) {
-        self.dirty = false;
+        self.changed = 0;
     }
 }
 
@@ -173,19 +331,46 @@ impl<I1: In> InputSet for FixedInputSet<(I1,)> {
         self.id
     }
 
-    fn push(&mut self, id: usize, event: &dyn Any) {
-        match id {
-            0 => self.dirty |= self.inputs.0.push(event.downcast_ref::<I1::Event>().unwrap()),
-            _ => unreachable!(),
-        }
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<InPin> {
+        vec![InPin {
+            pin_id: 0,
+            event_type_id: TypeId::of::<I1::Event>(),
+            event_type_name: any::type_name::<I1::Event>(),
+        }]
+    }
+
+    fn push(&mut self, id: usize, event: &dyn Any) -> Result<(), Error> {
+        let changed = match id {
+            0 => self.inputs.0.push(downcast::<I1::Event>(event, self.id.into(), id)?),
+            _ => {
+                return Err(Error::PinNotFound {
+                    set: self.id.into(),
+                    index: id,
+                })
+            }
+        };
+        self.changed |= (changed as u64) << id;
+        Ok(())
     }
 
     fn is_dirty(&self) -> bool {
-        self.dirty
+        self.changed != 0
+    }
+
+    fn has_values(&self) -> bool {
+        self.inputs.0.has_value()
+    }
+
+    fn changed(&self, id: usize) -> bool {
+        self.is_changed(id)
     }
 
     fn reset_dirty(&mut self) {
-        self.dirty = false;
+        self.changed = 0;
     }
 }
 
@@ -194,20 +379,54 @@ impl<I1: In, I2: In> InputSet for FixedInputSet<(I1, I2)> {
         self.id
     }
 
-    fn push(&mut self, id: usize, event: &dyn Any) {
-        match id {
-            0 => self.dirty |= self.inputs.0.push(event.downcast_ref::<I1::Event>().unwrap()),
-            1 => self.dirty |= self.inputs.1.push(event.downcast_ref::<I2::Event>().unwrap()),
-            _ => unreachable!(),
-        }
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<InPin> {
+        vec![
+            InPin {
+                pin_id: 0,
+                event_type_id: TypeId::of::<I1::Event>(),
+                event_type_name: any::type_name::<I1::Event>(),
+            },
+            InPin {
+                pin_id: 1,
+                event_type_id: TypeId::of::<I2::Event>(),
+                event_type_name: any::type_name::<I2::Event>(),
+            },
+        ]
+    }
+
+    fn push(&mut self, id: usize, event: &dyn Any) -> Result<(), Error> {
+        let changed = match id {
+            0 => self.inputs.0.push(downcast::<I1::Event>(event, self.id.into(), id)?),
+            1 => self.inputs.1.push(downcast::<I2::Event>(event, self.id.into(), id)?),
+            _ => {
+                return Err(Error::PinNotFound {
+                    set: self.id.into(),
+                    index: id,
+                })
+            }
+        };
+        self.changed |= (changed as u64) << id;
+        Ok(())
     }
 
     fn is_dirty(&self) -> bool {
-        self.dirty
+        self.changed != 0
+    }
+
+    fn has_values(&self) -> bool {
+        self.inputs.0.has_value() && self.inputs.1.has_value()
+    }
+
+    fn changed(&self, id: usize) -> bool {
+        self.is_changed(id)
     }
 
     fn reset_dirty(&mut self) {
-        self.dirty = false;
+        self.changed = 0;
     }
 }
 
@@ -216,21 +435,60 @@ impl<I1: In, I2: In, I3: In> InputSet for FixedInputSet<(I1, I2, I3)> {
         self.id
     }
 
-    fn push(&mut self, id: usize, event: &dyn Any) {
-        match id {
-            0 => self.dirty |= self.inputs.0.push(event.downcast_ref::<I1::Event>().unwrap()),
-            1 => self.dirty |= self.inputs.1.push(event.downcast_ref::<I2::Event>().unwrap()),
-            2 => self.dirty |= self.inputs.2.push(event.downcast_ref::<I3::Event>().unwrap()),
-            _ => unreachable!(),
-        }
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<InPin> {
+        vec![
+            InPin {
+                pin_id: 0,
+                event_type_id: TypeId::of::<I1::Event>(),
+                event_type_name: any::type_name::<I1::Event>(),
+            },
+            InPin {
+                pin_id: 1,
+                event_type_id: TypeId::of::<I2::Event>(),
+                event_type_name: any::type_name::<I2::Event>(),
+            },
+            InPin {
+                pin_id: 2,
+                event_type_id: TypeId::of::<I3::Event>(),
+                event_type_name: any::type_name::<I3::Event>(),
+            },
+        ]
+    }
+
+    fn push(&mut self, id: usize, event: &dyn Any) -> Result<(), Error> {
+        let changed = match id {
+            0 => self.inputs.0.push(downcast::<I1::Event>(event, self.id.into(), id)?),
+            1 => self.inputs.1.push(downcast::<I2::Event>(event, self.id.into(), id)?),
+            2 => self.inputs.2.push(downcast::<I3::Event>(event, self.id.into(), id)?),
+            _ => {
+                return Err(Error::PinNotFound {
+                    set: self.id.into(),
+                    index: id,
+                })
+            }
+        };
+        self.changed |= (changed as u64) << id;
+        Ok(())
     }
 
     fn is_dirty(&self) -> bool {
-        self.dirty
+        self.changed != 0
+    }
+
+    fn has_values(&self) -> bool {
+        self.inputs.0.has_value() && self.inputs.1.has_value() && self.inputs.2.has_value()
+    }
+
+    fn changed(&self, id: usize) -> bool {
+        self.is_changed(id)
     }
 
     fn reset_dirty(&mut self) {
-        self.dirty = false;
+        self.changed = 0;
     }
 }
 
@@ -239,22 +497,66 @@ impl<I1: In, I2: In, I3: In, I4: In> InputSet for FixedInputSet<(I1, I2, I3, I4)
         self.id
     }
 
-    fn push(&mut self, id: usize, event: &dyn Any) {
-        match id {
-            0 => self.dirty |= self.inputs.0.push(event.downcast_ref::<I1::Event>().unwrap()),
-            1 => self.dirty |= self.inputs.1.push(event.downcast_ref::<I2::Event>().unwrap()),
-            2 => self.dirty |= self.inputs.2.push(event.downcast_ref::<I3::Event>().unwrap()),
-            3 => self.dirty |= self.inputs.3.push(event.downcast_ref::<I4::Event>().unwrap()),
-            _ => unreachable!(),
-        }
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<InPin> {
+        vec![
+            InPin {
+                pin_id: 0,
+                event_type_id: TypeId::of::<I1::Event>(),
+                event_type_name: any::type_name::<I1::Event>(),
+            },
+            InPin {
+                pin_id: 1,
+                event_type_id: TypeId::of::<I2::Event>(),
+                event_type_name: any::type_name::<I2::Event>(),
+            },
+            InPin {
+                pin_id: 2,
+                event_type_id: TypeId::of::<I3::Event>(),
+                event_type_name: any::type_name::<I3::Event>(),
+            },
+            InPin {
+                pin_id: 3,
+                event_type_id: TypeId::of::<I4::Event>(),
+                event_type_name: any::type_name::<I4::Event>(),
+            },
+        ]
+    }
+
+    fn push(&mut self, id: usize, event: &dyn Any) -> Result<(), Error> {
+        let changed = match id {
+            0 => self.inputs.0.push(downcast::<I1::Event>(event, self.id.into(), id)?),
+            1 => self.inputs.1.push(downcast::<I2::Event>(event, self.id.into(), id)?),
+            2 => self.inputs.2.push(downcast::<I3::Event>(event, self.id.into(), id)?),
+            3 => self.inputs.3.push(downcast::<I4::Event>(event, self.id.into(), id)?),
+            _ => {
+                return Err(Error::PinNotFound {
+                    set: self.id.into(),
+                    index: id,
+                })
+            }
+        };
+        self.changed |= (changed as u64) << id;
+        Ok(())
     }
 
     fn is_dirty(&self) -> bool {
-        self.dirty
+        self.changed != 0
+    }
+
+    fn has_values(&self) -> bool {
+        self.inputs.0.has_value() && self.inputs.1.has_value() && self.inputs.2.has_value() && self.inputs.3.has_value()
+    }
+
+    fn changed(&self, id: usize) -> bool {
+        self.is_changed(id)
     }
 
     fn reset_dirty(&mut self) {
-        self.dirty = false;
+        self.changed = 0;
     }
 }
 
@@ -263,23 +565,76 @@ impl<I1: In, I2: In, I3: In, I4: In, I5: In> InputSet for FixedInputSet<(I1, I2,
         self.id
     }
 
-    fn push(&mut self, id: usize, event: &dyn Any) {
-        match id {
-            0 => self.dirty |= self.inputs.0.push(event.downcast_ref::<I1::Event>().unwrap()),
-            1 => self.dirty |= self.inputs.1.push(event.downcast_ref::<I2::Event>().unwrap()),
-            2 => self.dirty |= self.inputs.2.push(event.downcast_ref::<I3::Event>().unwrap()),
-            3 => self.dirty |= self.inputs.3.push(event.downcast_ref::<I4::Event>().unwrap()),
-            4 => self.dirty |= self.inputs.4.push(event.downcast_ref::<I5::Event>().unwrap()),
-            _ => unreachable!(),
-        }
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<InPin> {
+        vec![
+            InPin {
+                pin_id: 0,
+                event_type_id: TypeId::of::<I1::Event>(),
+                event_type_name: any::type_name::<I1::Event>(),
+            },
+            InPin {
+                pin_id: 1,
+                event_type_id: TypeId::of::<I2::Event>(),
+                event_type_name: any::type_name::<I2::Event>(),
+            },
+            InPin {
+                pin_id: 2,
+                event_type_id: TypeId::of::<I3::Event>(),
+                event_type_name: any::type_name::<I3::Event>(),
+            },
+            InPin {
+                pin_id: 3,
+                event_type_id: TypeId::of::<I4::Event>(),
+                event_type_name: any::type_name::<I4::Event>(),
+            },
+            InPin {
+                pin_id: 4,
+                event_type_id: TypeId::of::<I5::Event>(),
+                event_type_name: any::type_name::<I5::Event>(),
+            },
+        ]
+    }
+
+    fn push(&mut self, id: usize, event: &dyn Any) -> Result<(), Error> {
+        let changed = match id {
+            0 => self.inputs.0.push(downcast::<I1::Event>(event, self.id.into(), id)?),
+            1 => self.inputs.1.push(downcast::<I2::Event>(event, self.id.into(), id)?),
+            2 => self.inputs.2.push(downcast::<I3::Event>(event, self.id.into(), id)?),
+            3 => self.inputs.3.push(downcast::<I4::Event>(event, self.id.into(), id)?),
+            4 => self.inputs.4.push(downcast::<I5::Event>(event, self.id.into(), id)?),
+            _ => {
+                return Err(Error::PinNotFound {
+                    set: self.id.into(),
+                    index: id,
+                })
+            }
+        };
+        self.changed |= (changed as u64) << id;
+        Ok(())
     }
 
     fn is_dirty(&self) -> bool {
-        self.dirty
+        self.changed != 0
+    }
+
+    fn has_values(&self) -> bool {
+        self.inputs.0.has_value()
+            && self.inputs.1.has_value()
+            && self.inputs.2.has_value()
+            && self.inputs.3.has_value()
+            && self.inputs.4.has_value()
+    }
+
+    fn changed(&self, id: usize) -> bool {
+        self.is_changed(id)
     }
 
     fn reset_dirty(&mut self) {
-        self.dirty = false;
+        self.changed = 0;
     }
 }
 
@@ -287,17 +642,31 @@ impl<I1: In, I2: In, I3: In, I4: In, I5: In> InputSet for FixedInputSet<(I1, I2,
 #[derive(Clone)]
 pub struct InHandle {
     input_set: Weak<RefCell<dyn InputSet>>,
+    set_id: InputSetId,
+    owner: SetOwner,
     event_type: TypeId,
+    event_type_name: &'static str,
     pin_id: usize,
+    /// The input set is the buffer of a delayed connection, the deliveries are traced when the buffer is flushed
+    delayed: bool,
 }
 
 impl InHandle {
-    pub fn new<I: InputSet>(input_set: &Rc<RefCell<I>>, pin_id: usize, event_type: TypeId) -> Self {
+    pub fn new<I: InputSet>(
+        input_set: &Rc<RefCell<I>>,
+        pin_id: usize,
+        event_type: TypeId,
+        event_type_name: &'static str,
+    ) -> Self {
         let weak = Rc::downgrade(input_set);
         Self {
             input_set: weak,
+            set_id: input_set.borrow().id(),
+            owner: input_set.borrow().owner().clone(),
             event_type,
+            event_type_name,
             pin_id,
+            delayed: false,
         }
     }
 
@@ -305,15 +674,87 @@ impl InHandle {
         self.event_type
     }
 
-    pub(in crate::frp) fn push(&self, event: &dyn Any) {
-        assert_eq!(event.type_id(), self.event_type);
+    pub fn event_type_name(&self) -> &'static str {
+        self.event_type_name
+    }
+
+    /// Get the system the input set of the handle was added to.
+    pub fn system_id(&self) -> Option<SystemId> {
+        self.owner.get()
+    }
+
+    pub fn set_id(&self) -> InputSetId {
+        self.set_id
+    }
+
+    pub fn pin_id(&self) -> usize {
+        self.pin_id
+    }
+
+    /// Get the identity and the event type of the pin.
+    pub fn pin_info(&self) -> PinInfo {
+        PinInfo {
+            set: self.set_id.into(),
+            pin_id: self.pin_id,
+            event_type_name: self.event_type_name,
+        }
+    }
+
+    /// Returns if both handles refer to the same input pin.
+    pub(in crate::frp) fn is_same_pin(&self, other: &InHandle) -> bool {
+        self.set_id == other.set_id && self.pin_id == other.pin_id
+    }
+
+    /// Check that the pin is present in the input set and it receives the event type of the handle.
+    pub(in crate::frp) fn validate(&self) -> Result<(), Error> {
+        let input_set = self.input_set.upgrade().ok_or(Error::InputNotFound)?;
+        let pins = input_set.borrow().pins();
+        let pin = pins
+            .iter()
+            .find(|pin| pin.pin_id == self.pin_id)
+            .ok_or(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: self.pin_id,
+            })?;
+        if pin.event_type_id == self.event_type {
+            Ok(())
+        } else {
+            Err(Error::TypeMismatch {
+                expected: pin.event_type_name,
+                found: self.event_type_name,
+            })
+        }
+    }
+
+    /// Mark the handle as the input of a delayed connection.
+    pub(in crate::frp) fn into_delayed(mut self) -> Self {
+        self.delayed = true;
+        self
+    }
+
+    pub(in crate::frp) fn is_delayed(&self) -> bool {
+        self.delayed
+    }
+
+    /// Returns if the input set of the handle is still alive.
+    pub(in crate::frp) fn is_alive(&self) -> bool {
+        self.input_set.strong_count() > 0
+    }
+
+    /// Push an event to the input. If the input set was dropped, false is returned.
+    pub(in crate::frp) fn push(&self, event: &dyn Any) -> bool {
         if let Some(input) = self.input_set.upgrade() {
-            input.borrow_mut().push(self.pin_id, event);
+            // NO-PANIC: the handles are validated when they are connected, thus the pin exists and has the type
+            input.borrow_mut().push(self.pin_id, event).unwrap();
+            true
+        } else {
+            false
         }
     }
 }
 
 /// Handle to an input in an input set.
+#[derive(Clone)]
 pub struct TypedInHandle<T: Event> {
     handle: InHandle,
     ph: PhantomData<T>,
@@ -321,27 +762,37 @@ pub struct TypedInHandle<T: Event> {
 
 impl<T: Event> TypedInHandle<T> {
     pub fn new<I: InputSet>(input_set: &Rc<RefCell<I>>, pin_id: usize) -> Self {
-        Self::from(InHandle::new(input_set, pin_id, TypeId::of::<T>()))
+        Self {
+            handle: InHandle::new(input_set, pin_id, TypeId::of::<T>(), any::type_name::<T>()),
+            ph: PhantomData,
+        }
     }
 
     pub fn handle(&self) -> &InHandle {
         &self.handle
     }
 
-    pub(in crate::frp) fn push(&self, event: &T) {
-        self.handle().push(event);
+    /// Push an event to the input. If the input set was dropped, false is returned.
+    pub(in crate::frp) fn push(&self, event: &T) -> bool {
+        self.handle().push(event)
     }
 }
 
-impl<T: Event> From<InHandle> for TypedInHandle<T> {
-    /// Convert from a type erase handle.
-    /// #Panic
-    /// This function may panic if the types are not matching.
-    fn from(handle: InHandle) -> Self {
-        assert_eq!(handle.event_type, TypeId::of::<T>());
-        Self {
-            handle,
-            ph: PhantomData,
+impl<T: Event> TryFrom<InHandle> for TypedInHandle<T> {
+    type Error = Error;
+
+    /// Convert from a type erased handle. If the types are not matching, an error is returned.
+    fn try_from(handle: InHandle) -> Result<Self, Self::Error> {
+        if handle.event_type == TypeId::of::<T>() {
+            Ok(Self {
+                handle,
+                ph: PhantomData,
+            })
+        } else {
+            Err(Error::TypeMismatch {
+                expected: any::type_name::<T>(),
+                found: handle.event_type_name,
+            })
         }
     }
 }
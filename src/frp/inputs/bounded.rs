@@ -0,0 +1,105 @@
+use crate::frp::{Event, In};
+use std::{
+    collections::{vec_deque, VecDeque},
+    num::NonZeroUsize,
+    ops::Deref,
+};
+
+/// Handling of the events received by a full `Bounded` input.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OverflowPolicy {
+    /// The oldest stored event is removed to make room for the new one.
+    DropOldest,
+    /// The new event is ignored and the behaviour is not triggered by it.
+    DropNewest,
+    /// The new event is ignored, but the behaviour is triggered to inspect the overflow.
+    Flag,
+}
+
+/// Store the events in a ring buffer with a fixed capacity.
+pub struct Bounded<T: Event> {
+    events: VecDeque<T>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    /// Number of the events dropped since the overflow was cleared
+    dropped: usize,
+}
+
+impl<T: Event> Bounded<T> {
+    /// Create an empty buffer, a buffer without room for an event cannot be created.
+    pub fn new(capacity: NonZeroUsize, policy: OverflowPolicy) -> Self {
+        let capacity = capacity.get();
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+            policy,
+            dropped: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Returns if any event was dropped since the overflow was cleared.
+    pub fn overflowed(&self) -> bool {
+        self.dropped > 0
+    }
+
+    /// Get the number of the events dropped since the overflow was cleared.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn clear_overflow(&mut self) {
+        self.dropped = 0;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.events.pop_front()
+    }
+
+    /// Remove all the stored events and clear the overflow.
+    pub fn drain(&mut self) -> vec_deque::Drain<'_, T> {
+        self.dropped = 0;
+        self.events.drain(..)
+    }
+}
+
+impl<T: Event> Deref for Bounded<T> {
+    type Target = VecDeque<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.events
+    }
+}
+
+impl<T: Event> In for Bounded<T> {
+    type Event = T;
+
+    fn push(&mut self, event: &Self::Event) -> bool {
+        if self.events.len() < self.capacity {
+            self.events.push_back(event.clone());
+            return true;
+        }
+
+        self.dropped += 1;
+        match self.policy {
+            OverflowPolicy::DropOldest => {
+                self.events.pop_front();
+                self.events.push_back(event.clone());
+                true
+            }
+            OverflowPolicy::DropNewest => false,
+            OverflowPolicy::Flag => true,
+        }
+    }
+
+    fn has_value(&self) -> bool {
+        !self.events.is_empty()
+    }
+}
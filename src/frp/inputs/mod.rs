@@ -1,3 +1,9 @@
+mod bounded;
+pub use self::bounded::*;
+mod sampled;
+pub use self::sampled::*;
+mod store_if_changed;
+pub use self::store_if_changed::*;
 mod store_last;
 pub use self::store_last::*;
 mod unbounded;
@@ -0,0 +1,44 @@
+use crate::frp::In;
+use std::ops::{Deref, DerefMut};
+
+/// Passive input that stores the events by the wrapped input, but never triggers the behaviour.
+/// The stored value is observed only when the behaviour is called by a change of another input.
+#[derive(Default)]
+pub struct Sampled<I: In>(I);
+
+impl<I: In> Sampled<I> {
+    pub fn new(input: I) -> Self {
+        Self(input)
+    }
+
+    pub fn into_inner(self) -> I {
+        self.0
+    }
+}
+
+impl<I: In> Deref for Sampled<I> {
+    type Target = I;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<I: In> DerefMut for Sampled<I> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<I: In> In for Sampled<I> {
+    type Event = I::Event;
+
+    fn push(&mut self, event: &Self::Event) -> bool {
+        self.0.push(event);
+        false
+    }
+
+    fn has_value(&self) -> bool {
+        self.0.has_value()
+    }
+}
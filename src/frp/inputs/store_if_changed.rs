@@ -0,0 +1,119 @@
+use crate::frp::{Event, In};
+
+/// Store the last event like `StoreLast`, but an event equal to the stored one is ignored and does not
+/// trigger the behaviour. Thus the unchanged values are not propagated in the graph.
+pub struct StoreIfChanged<T: Event + PartialEq>(Option<T>);
+
+impl<T: Event + PartialEq> Default for StoreIfChanged<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T: Event + PartialEq> StoreIfChanged<T> {
+    pub fn new(event: T) -> Self {
+        Self(Some(event))
+    }
+
+    pub fn try_get(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+
+    /// Take the stored value, the next event is always considered as a change.
+    pub fn take(&mut self) -> Option<T> {
+        self.0.take()
+    }
+
+    /// Get the stored value.
+    /// #Panic
+    /// This function may panic if no value is available.
+    pub fn get(&self) -> &T {
+        self.try_get().unwrap()
+    }
+}
+
+impl<T: Event + PartialEq> In for StoreIfChanged<T> {
+    type Event = T;
+
+    fn push(&mut self, event: &Self::Event) -> bool {
+        if self.0.as_ref() == Some(event) {
+            false
+        } else {
+            self.0 = Some(event.clone());
+            true
+        }
+    }
+
+    fn has_value(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+/// Floating point values compared with a tolerance.
+pub trait Approx: Event + Copy {
+    fn is_close(self, other: Self, tolerance: Self) -> bool;
+}
+
+impl Approx for f32 {
+    fn is_close(self, other: Self, tolerance: Self) -> bool {
+        (self - other).abs() <= tolerance
+    }
+}
+
+impl Approx for f64 {
+    fn is_close(self, other: Self, tolerance: Self) -> bool {
+        (self - other).abs() <= tolerance
+    }
+}
+
+/// Store the last value, but a value within the tolerance of the stored one is ignored and does not
+/// trigger the behaviour. The values are compared to the last stored value, thus a slow drift is
+/// propagated once it exceeds the tolerance.
+pub struct StoreIfChangedApprox<T: Approx> {
+    value: Option<T>,
+    tolerance: T,
+}
+
+impl<T: Approx> StoreIfChangedApprox<T> {
+    pub fn new(tolerance: T) -> Self {
+        Self { value: None, tolerance }
+    }
+
+    pub fn tolerance(&self) -> T {
+        self.tolerance
+    }
+
+    pub fn try_get(&self) -> Option<T> {
+        self.value
+    }
+
+    /// Take the stored value, the next value is always considered as a change.
+    pub fn take(&mut self) -> Option<T> {
+        self.value.take()
+    }
+
+    /// Get the stored value.
+    /// #Panic
+    /// This function may panic if no value is available.
+    pub fn get(&self) -> T {
+        self.value.unwrap()
+    }
+}
+
+impl<T: Approx> In for StoreIfChangedApprox<T> {
+    type Event = T;
+
+    fn push(&mut self, event: &Self::Event) -> bool {
+        match self.value {
+            Some(value) if value.is_close(*event, self.tolerance) => false,
+            _ => {
+                self.value = Some(*event);
+                true
+            }
+        }
+    }
+
+    fn has_value(&self) -> bool {
+        self.value.is_some()
+    }
+}
@@ -35,4 +35,8 @@ impl<T: Event> In for StoreLast<T> {
         self.0 = Some(event.clone());
         true
     }
+
+    fn has_value(&self) -> bool {
+        self.0.is_some()
+    }
 }
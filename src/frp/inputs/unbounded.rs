@@ -30,4 +30,8 @@ impl<T: Event> In for Unbounded<T> {
         self.0.push(event.clone());
         true
     }
+
+    fn has_value(&self) -> bool {
+        !self.0.is_empty()
+    }
 }
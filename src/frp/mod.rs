@@ -8,6 +8,24 @@ mod behaviour;
 pub use self::behaviour::*;
 mod system;
 pub use self::system::*;
+mod transaction;
+pub use self::transaction::*;
+mod system_graph;
+pub use self::system_graph::*;
+mod description;
+pub use self::description::*;
+mod registry;
+pub use self::registry::*;
+mod stats;
+pub use self::stats::*;
+mod trace;
+pub use self::trace::*;
+mod delay;
+pub(in crate::frp) use self::delay::*;
+mod system_output;
+pub(in crate::frp) use self::system_output::*;
+mod worklist;
+pub(in crate::frp) use self::worklist::*;
 
 pub mod behaviours;
 pub mod inputs;
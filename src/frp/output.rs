@@ -1,7 +1,9 @@
-use crate::frp::{next_id, Error, Event, InHandle, TypedInHandle};
+use crate::frp::{
+    downcast, next_id, Error, Event, InHandle, PinInfo, PinTracer, SetId, SetOwner, SystemId, TraceSink, TypedInHandle,
+};
 use downcast_rs::{impl_downcast, Downcast};
 use std::{
-    any::{Any, TypeId},
+    any::{self, Any, TypeId},
     cell::RefCell,
     marker::PhantomData,
     ops::{Deref, DerefMut},
@@ -11,58 +13,136 @@ use std::{
 /// An output of a `Behaviour`.
 pub struct Out<T: Event> {
     listeners: Vec<TypedInHandle<T>>,
+    /// Number of the events sent on the output
+    sent: usize,
+    tracer: Option<PinTracer>,
 }
 
 impl<T: Event> Default for Out<T> {
     fn default() -> Self {
-        Self { listeners: Vec::new() }
+        Self {
+            listeners: Vec::new(),
+            sent: 0,
+            tracer: None,
+        }
     }
 }
 
 impl<T: Event> Out<T> {
-    pub fn send(&mut self, event: &T) {
-        for listener in &self.listeners {
-            listener.push(event);
+    /// Describe the pin with the given id.
+    pub fn describe(&self, pin_id: usize) -> OutPin {
+        OutPin {
+            pin_id,
+            event_type_id: TypeId::of::<T>(),
+            event_type_name: any::type_name::<T>(),
+            sent: self.sent,
+            connections: self
+                .listeners
+                .iter()
+                .map(|listener| listener.handle().clone())
+                .collect(),
         }
     }
+
+    /// Call the visitor with the pin id, the number of the sent events and the connected inputs of the output.
+    pub fn visit_sent(
+        &self,
+        pin_id: usize,
+        visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>),
+    ) {
+        visitor(
+            pin_id,
+            self.sent,
+            &mut self.listeners.iter().map(|listener| listener.handle()),
+        );
+    }
+
+    /// Send an event to all the connected inputs. The connections to the dropped input sets are removed.
+    pub fn send(&mut self, event: &T) {
+        self.sent += 1;
+        let tracer = &self.tracer;
+        self.listeners.retain(|listener| {
+            if !listener.handle().is_alive() {
+                log::warn!("Connection to a dropped input set was removed");
+                return false;
+            }
+            // the delayed connections are traced when the events are forwarded to the target
+            if let Some(tracer) = tracer.as_ref().filter(|_| !listener.handle().is_delayed()) {
+                tracer.trace(listener.handle(), event);
+            }
+            listener.push(event)
+        });
+    }
+
+    /// Install or remove the tracing of the deliveries from the output.
+    pub fn set_tracer(&mut self, set_id: OutputSetId, pin_id: usize, sink: Option<&TraceSink>) {
+        self.tracer = sink.map(|sink| PinTracer::new(sink, set_id, pin_id));
+    }
 }
 
 /// Type erased version of an `Out`
 pub(in crate::frp) trait GeneralOut: Downcast {
-    /// Get the type of the produced event
-    fn event_type_id(&self) -> TypeId;
-
-    /// Send an event to all the connected `In`.
-    /// #Panic
-    /// This function may panic if the event cannect be downcasted to the type of the input.
-    fn send_any(&mut self, event: &dyn Any) -> Result<(), Error>;
+    /// Send an event to all the connected `In`. If the event cannot be downcasted to the type of the output,
+    /// an error is returned.
+    fn send_any(&mut self, set: SetId, pin_id: usize, event: &dyn Any) -> Result<(), Error>;
 
-    /// Send an event to all the connected `In`.
-    /// #Panic
-    /// This function may panic if the event cannect be downcasted to the type of the input.
+    /// Connect an input. If the input has another event type or the pin is not present in its input set,
+    /// an error is returned.
     fn connect_any(&mut self, handle: InHandle) -> Result<(), Error>;
+
+    /// Describe the pin with the given id.
+    fn describe_any(&self, pin_id: usize) -> OutPin;
+
+    /// Call the visitor with the pin id, the number of the sent events and the connected inputs of the output.
+    fn visit_sent_any(&self, pin_id: usize, visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>));
+
+    /// Install or remove the tracing of the deliveries from the output.
+    fn set_tracer_any(&mut self, set_id: OutputSetId, pin_id: usize, sink: Option<&TraceSink>);
+
+    /// Remove the connections to the inputs selected by the filter and return the handles of the removed inputs.
+    fn disconnect_any(&mut self, filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<InHandle>;
 }
 impl_downcast!(GeneralOut);
 
 impl<T: Event> GeneralOut for Out<T> {
-    fn event_type_id(&self) -> TypeId {
-        TypeId::of::<T>()
+    fn send_any(&mut self, set: SetId, pin_id: usize, event: &dyn Any) -> Result<(), Error> {
+        self.send(downcast::<T>(event, set, pin_id)?);
+        Ok(())
     }
 
-    fn send_any(&mut self, event: &dyn Any) -> Result<(), Error> {
-        let event = event.downcast_ref::<T>().ok_or(Error::UnexpectedEventType)?;
-        self.send(event);
+    fn connect_any(&mut self, handle: InHandle) -> Result<(), Error> {
+        handle.validate()?;
+        self.listeners.push(TypedInHandle::try_from(handle)?);
         Ok(())
     }
 
-    fn connect_any(&mut self, handle: InHandle) -> Result<(), Error> {
-        if handle.event_type_id() == TypeId::of::<T>() {
-            let handle = TypedInHandle::<T>::from(handle);
-            self.listeners.push(handle);
-            Ok(())
-        } else {
-            Err(Error::UnexpectedEventType)
-        }
+    fn describe_any(&self, pin_id: usize) -> OutPin {
+        self.describe(pin_id)
+    }
+
+    fn visit_sent_any(
+        &self,
+        pin_id: usize,
+        visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>),
+    ) {
+        self.visit_sent(pin_id, visitor)
+    }
+
+    fn set_tracer_any(&mut self, set_id: OutputSetId, pin_id: usize, sink: Option<&TraceSink>) {
+        self.set_tracer(set_id, pin_id, sink)
+    }
+
+    fn disconnect_any(&mut self, filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<InHandle> {
+        let mut removed = Vec::new();
+        self.listeners.retain(|listener| {
+            if filter(listener.handle()) {
+                removed.push(listener.handle().clone());
+                false
+            } else {
+                true
+            }
+        });
+        removed
     }
 }
 
@@ -77,19 +157,54 @@ impl OutputSetId {
     }
 }
 
+/// Description of an output pin and its connections for the inspection of the graph.
+pub struct OutPin {
+    pub pin_id: usize,
+    pub event_type_id: TypeId,
+    pub event_type_name: &'static str,
+    /// Number of the events sent on the pin since its creation
+    pub sent: usize,
+    pub connections: Vec<InHandle>,
+}
+
 /// The output set of a `Behaviour`.
 pub trait OutputSet: 'static {
     fn id(&self) -> OutputSetId;
 
-    /// Try to connect a new input pin to the given output pin. If their types are not matching, an error is returned.
-    /// #Panic
-    /// This function may panic if the index of the output is invalid.
+    /// The system the set was added to.
+    fn owner(&self) -> &SetOwner;
+
+    /// Describe the output pins of the set.
+    fn pins(&self) -> Vec<OutPin>;
+
+    /// Call the visitor with the pin id, the number of the sent events and the connected inputs of each output pin.
+    /// Unlike `pins`, the handles of the connections are not cloned.
+    fn visit_sent(&self, visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>)) {
+        for pin in self.pins() {
+            visitor(pin.pin_id, pin.sent, &mut pin.connections.iter());
+        }
+    }
+
+    /// Install or remove the tracing of the deliveries from all the output pins.
+    fn set_tracer(&mut self, _sink: Option<&TraceSink>) {}
+
+    /// Try to connect a new input pin to the given output pin. If their types are not matching or the index of the
+    /// output is invalid, an error is returned.
     fn connect(&mut self, id: usize, in_handle: InHandle) -> Result<(), Error>;
+
+    /// Remove the connections of the given output pin to the inputs selected by the filter.
+    /// The handles of the removed inputs are returned, if the index of the output is invalid an error is returned.
+    fn disconnect(&mut self, id: usize, filter: &mut dyn FnMut(&InHandle) -> bool) -> Result<Vec<InHandle>, Error>;
+
+    /// Remove the connections of all the output pins to the inputs selected by the filter.
+    /// The removed connections are returned as the index of the output and the handle of the input.
+    fn disconnect_all(&mut self, filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<(usize, InHandle)>;
 }
 
 /// Dynamic set of outputs constructed programmatically.
 pub struct DynamicOutSet {
     set_id: OutputSetId,
+    owner: SetOwner,
     outputs: Vec<Box<dyn GeneralOut>>,
 }
 
@@ -97,6 +212,7 @@ impl Default for DynamicOutSet {
     fn default() -> Self {
         Self {
             set_id: OutputSetId::new(),
+            owner: SetOwner::default(),
             outputs: Vec::new(),
         }
     }
@@ -114,11 +230,28 @@ impl DynamicOutSet {
         if handle.set_id() == self.set_id {
             self.outputs
                 .get_mut(handle.pin_id())
-                .and_then(|o| (&mut **o).downcast_mut::<Out<T>>())
+                .and_then(|o| (**o).downcast_mut::<Out<T>>())
         } else {
             None
         }
     }
+
+    pub(in crate::frp) fn contains(&self, id: usize) -> bool {
+        id < self.outputs.len()
+    }
+
+    /// Send a type erased event on the output with the given id.
+    pub(in crate::frp) fn send_any(&mut self, id: usize, event: &dyn Any) -> Result<(), Error> {
+        let set = self.set_id.into();
+        self.get_any(id)?.send_any(set, id, event)
+    }
+
+    fn get_any(&mut self, id: usize) -> Result<&mut Box<dyn GeneralOut>, Error> {
+        self.outputs.get_mut(id).ok_or(Error::PinNotFound {
+            set: self.set_id.into(),
+            index: id,
+        })
+    }
 }
 
 impl OutputSet for DynamicOutSet {
@@ -126,14 +259,51 @@ impl OutputSet for DynamicOutSet {
         self.set_id
     }
 
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<OutPin> {
+        self.outputs
+            .iter()
+            .enumerate()
+            .map(|(pin_id, output)| output.describe_any(pin_id))
+            .collect()
+    }
+
+    fn visit_sent(&self, visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>)) {
+        for (pin_id, output) in self.outputs.iter().enumerate() {
+            output.visit_sent_any(pin_id, visitor);
+        }
+    }
+
+    fn set_tracer(&mut self, sink: Option<&TraceSink>) {
+        for (pin_id, output) in self.outputs.iter_mut().enumerate() {
+            output.set_tracer_any(self.set_id, pin_id, sink);
+        }
+    }
+
     fn connect(&mut self, id: usize, in_handle: InHandle) -> Result<(), Error> {
-        self.outputs[id].connect_any(in_handle)
+        self.get_any(id)?.connect_any(in_handle)
+    }
+
+    fn disconnect(&mut self, id: usize, filter: &mut dyn FnMut(&InHandle) -> bool) -> Result<Vec<InHandle>, Error> {
+        Ok(self.get_any(id)?.disconnect_any(filter))
+    }
+
+    fn disconnect_all(&mut self, filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<(usize, InHandle)> {
+        let mut removed = Vec::new();
+        for (id, output) in self.outputs.iter_mut().enumerate() {
+            removed.extend(output.disconnect_any(filter).into_iter().map(|handle| (id, handle)));
+        }
+        removed
     }
 }
 
 /// Static, compile time definition of a set of outputs.
 pub struct FixedOutSet<O: Default> {
     set_id: OutputSetId,
+    owner: SetOwner,
     outputs: O,
 }
 
@@ -141,6 +311,7 @@ impl<O: Default> Default for FixedOutSet<O> {
     fn default() -> Self {
         Self {
             set_id: OutputSetId::new(),
+            owner: SetOwner::default(),
             outputs: O::default(),
         }
     }
@@ -165,8 +336,30 @@ impl OutputSet for FixedOutSet<()> {
         self.set_id
     }
 
-    fn connect(&mut self, _id: usize, _in_handle: InHandle) -> Result<(), Error> {
-        panic!("Invalid id, OutputSet has no such pin");
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<OutPin> {
+        Vec::new()
+    }
+
+    fn connect(&mut self, id: usize, _in_handle: InHandle) -> Result<(), Error> {
+        Err(Error::PinNotFound {
+            set: self.set_id.into(),
+            index: id,
+        })
+    }
+
+    fn disconnect(&mut self, id: usize, _filter: &mut dyn FnMut(&InHandle) -> bool) -> Result<Vec<InHandle>, Error> {
+        Err(Error::PinNotFound {
+            set: self.set_id.into(),
+            index: id,
+        })
+    }
+
+    fn disconnect_all(&mut self, _filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<(usize, InHandle)> {
+        Vec::new()
     }
 }
 
@@ -175,12 +368,49 @@ impl<T1: Event> OutputSet for FixedOutSet<Out<T1>> {
         self.set_id
     }
 
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<OutPin> {
+        vec![self.outputs.describe(0)]
+    }
+
+    fn visit_sent(&self, visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>)) {
+        self.outputs.visit_sent(0, visitor);
+    }
+
+    fn set_tracer(&mut self, sink: Option<&TraceSink>) {
+        self.outputs.set_tracer(self.set_id, 0, sink);
+    }
+
     fn connect(&mut self, id: usize, in_handle: InHandle) -> Result<(), Error> {
         match id {
             0 => self.outputs.connect_any(in_handle),
-            _ => panic!("Invalid id, OutputSet has no such pin"),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
         }
     }
+
+    fn disconnect(&mut self, id: usize, filter: &mut dyn FnMut(&InHandle) -> bool) -> Result<Vec<InHandle>, Error> {
+        match id {
+            0 => Ok(self.outputs.disconnect_any(filter)),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
+        }
+    }
+
+    fn disconnect_all(&mut self, filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<(usize, InHandle)> {
+        self.outputs
+            .disconnect_any(filter)
+            .into_iter()
+            .map(|handle| (0, handle))
+            .collect()
+    }
 }
 
 impl<T1: Event> OutputSet for FixedOutSet<(Out<T1>,)> {
@@ -188,12 +418,53 @@ impl<T1: Event> OutputSet for FixedOutSet<(Out<T1>,)> {
         self.set_id
     }
 
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<OutPin> {
+        vec![self.outputs.0.describe(0)]
+    }
+
+    fn visit_sent(&self, visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>)) {
+        self.outputs.0.visit_sent(0, visitor);
+    }
+
+    fn set_tracer(&mut self, sink: Option<&TraceSink>) {
+        self.outputs.0.set_tracer(self.set_id, 0, sink);
+    }
+
     fn connect(&mut self, id: usize, in_handle: InHandle) -> Result<(), Error> {
         match id {
             0 => self.outputs.0.connect_any(in_handle),
-            _ => panic!("Invalid id, OutputSet has no such pin"),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
         }
     }
+
+    fn disconnect(&mut self, id: usize, filter: &mut dyn FnMut(&InHandle) -> bool) -> Result<Vec<InHandle>, Error> {
+        match id {
+            0 => Ok(self.outputs.0.disconnect_any(filter)),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
+        }
+    }
+
+    fn disconnect_all(&mut self, filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<(usize, InHandle)> {
+        let mut removed = Vec::new();
+        removed.extend(
+            self.outputs
+                .0
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (0, handle)),
+        );
+        removed
+    }
 }
 
 impl<T1: Event, T2: Event> OutputSet for FixedOutSet<(Out<T1>, Out<T2>)> {
@@ -201,13 +472,64 @@ impl<T1: Event, T2: Event> OutputSet for FixedOutSet<(Out<T1>, Out<T2>)> {
         self.set_id
     }
 
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<OutPin> {
+        vec![self.outputs.0.describe(0), self.outputs.1.describe(1)]
+    }
+
+    fn visit_sent(&self, visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>)) {
+        self.outputs.0.visit_sent(0, visitor);
+        self.outputs.1.visit_sent(1, visitor);
+    }
+
+    fn set_tracer(&mut self, sink: Option<&TraceSink>) {
+        self.outputs.0.set_tracer(self.set_id, 0, sink);
+        self.outputs.1.set_tracer(self.set_id, 1, sink);
+    }
+
     fn connect(&mut self, id: usize, in_handle: InHandle) -> Result<(), Error> {
         match id {
             0 => self.outputs.0.connect_any(in_handle),
             1 => self.outputs.1.connect_any(in_handle),
-            _ => panic!("Invalid id, OutputSet has no such pin"),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
+        }
+    }
+
+    fn disconnect(&mut self, id: usize, filter: &mut dyn FnMut(&InHandle) -> bool) -> Result<Vec<InHandle>, Error> {
+        match id {
+            0 => Ok(self.outputs.0.disconnect_any(filter)),
+            1 => Ok(self.outputs.1.disconnect_any(filter)),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
         }
     }
+
+    fn disconnect_all(&mut self, filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<(usize, InHandle)> {
+        let mut removed = Vec::new();
+        removed.extend(
+            self.outputs
+                .0
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (0, handle)),
+        );
+        removed.extend(
+            self.outputs
+                .1
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (1, handle)),
+        );
+        removed
+    }
 }
 
 impl<T1: Event, T2: Event, T3: Event> OutputSet for FixedOutSet<(Out<T1>, Out<T2>, Out<T3>)> {
@@ -215,14 +537,79 @@ impl<T1: Event, T2: Event, T3: Event> OutputSet for FixedOutSet<(Out<T1>, Out<T2
         self.set_id
     }
 
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<OutPin> {
+        vec![
+            self.outputs.0.describe(0),
+            self.outputs.1.describe(1),
+            self.outputs.2.describe(2),
+        ]
+    }
+
+    fn visit_sent(&self, visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>)) {
+        self.outputs.0.visit_sent(0, visitor);
+        self.outputs.1.visit_sent(1, visitor);
+        self.outputs.2.visit_sent(2, visitor);
+    }
+
+    fn set_tracer(&mut self, sink: Option<&TraceSink>) {
+        self.outputs.0.set_tracer(self.set_id, 0, sink);
+        self.outputs.1.set_tracer(self.set_id, 1, sink);
+        self.outputs.2.set_tracer(self.set_id, 2, sink);
+    }
+
     fn connect(&mut self, id: usize, in_handle: InHandle) -> Result<(), Error> {
         match id {
             0 => self.outputs.0.connect_any(in_handle),
             1 => self.outputs.1.connect_any(in_handle),
             2 => self.outputs.2.connect_any(in_handle),
-            _ => panic!("Invalid id, OutputSet has no such pin"),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
         }
     }
+
+    fn disconnect(&mut self, id: usize, filter: &mut dyn FnMut(&InHandle) -> bool) -> Result<Vec<InHandle>, Error> {
+        match id {
+            0 => Ok(self.outputs.0.disconnect_any(filter)),
+            1 => Ok(self.outputs.1.disconnect_any(filter)),
+            2 => Ok(self.outputs.2.disconnect_any(filter)),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
+        }
+    }
+
+    fn disconnect_all(&mut self, filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<(usize, InHandle)> {
+        let mut removed = Vec::new();
+        removed.extend(
+            self.outputs
+                .0
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (0, handle)),
+        );
+        removed.extend(
+            self.outputs
+                .1
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (1, handle)),
+        );
+        removed.extend(
+            self.outputs
+                .2
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (2, handle)),
+        );
+        removed
+    }
 }
 
 impl<T1: Event, T2: Event, T3: Event, T4: Event> OutputSet for FixedOutSet<(Out<T1>, Out<T2>, Out<T3>, Out<T4>)> {
@@ -230,15 +617,91 @@ impl<T1: Event, T2: Event, T3: Event, T4: Event> OutputSet for FixedOutSet<(Out<
         self.set_id
     }
 
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<OutPin> {
+        vec![
+            self.outputs.0.describe(0),
+            self.outputs.1.describe(1),
+            self.outputs.2.describe(2),
+            self.outputs.3.describe(3),
+        ]
+    }
+
+    fn visit_sent(&self, visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>)) {
+        self.outputs.0.visit_sent(0, visitor);
+        self.outputs.1.visit_sent(1, visitor);
+        self.outputs.2.visit_sent(2, visitor);
+        self.outputs.3.visit_sent(3, visitor);
+    }
+
+    fn set_tracer(&mut self, sink: Option<&TraceSink>) {
+        self.outputs.0.set_tracer(self.set_id, 0, sink);
+        self.outputs.1.set_tracer(self.set_id, 1, sink);
+        self.outputs.2.set_tracer(self.set_id, 2, sink);
+        self.outputs.3.set_tracer(self.set_id, 3, sink);
+    }
+
     fn connect(&mut self, id: usize, in_handle: InHandle) -> Result<(), Error> {
         match id {
             0 => self.outputs.0.connect_any(in_handle),
             1 => self.outputs.1.connect_any(in_handle),
             2 => self.outputs.2.connect_any(in_handle),
             3 => self.outputs.3.connect_any(in_handle),
-            _ => panic!("Invalid id, OutputSet has no such pin"),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
+        }
+    }
+
+    fn disconnect(&mut self, id: usize, filter: &mut dyn FnMut(&InHandle) -> bool) -> Result<Vec<InHandle>, Error> {
+        match id {
+            0 => Ok(self.outputs.0.disconnect_any(filter)),
+            1 => Ok(self.outputs.1.disconnect_any(filter)),
+            2 => Ok(self.outputs.2.disconnect_any(filter)),
+            3 => Ok(self.outputs.3.disconnect_any(filter)),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
         }
     }
+
+    fn disconnect_all(&mut self, filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<(usize, InHandle)> {
+        let mut removed = Vec::new();
+        removed.extend(
+            self.outputs
+                .0
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (0, handle)),
+        );
+        removed.extend(
+            self.outputs
+                .1
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (1, handle)),
+        );
+        removed.extend(
+            self.outputs
+                .2
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (2, handle)),
+        );
+        removed.extend(
+            self.outputs
+                .3
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (3, handle)),
+        );
+        removed
+    }
 }
 
 impl<T1: Event, T2: Event, T3: Event, T4: Event, T5: Event> OutputSet
@@ -248,6 +711,36 @@ impl<T1: Event, T2: Event, T3: Event, T4: Event, T5: Event> OutputSet
         self.set_id
     }
 
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<OutPin> {
+        vec![
+            self.outputs.0.describe(0),
+            self.outputs.1.describe(1),
+            self.outputs.2.describe(2),
+            self.outputs.3.describe(3),
+            self.outputs.4.describe(4),
+        ]
+    }
+
+    fn visit_sent(&self, visitor: &mut dyn FnMut(usize, usize, &mut dyn Iterator<Item = &InHandle>)) {
+        self.outputs.0.visit_sent(0, visitor);
+        self.outputs.1.visit_sent(1, visitor);
+        self.outputs.2.visit_sent(2, visitor);
+        self.outputs.3.visit_sent(3, visitor);
+        self.outputs.4.visit_sent(4, visitor);
+    }
+
+    fn set_tracer(&mut self, sink: Option<&TraceSink>) {
+        self.outputs.0.set_tracer(self.set_id, 0, sink);
+        self.outputs.1.set_tracer(self.set_id, 1, sink);
+        self.outputs.2.set_tracer(self.set_id, 2, sink);
+        self.outputs.3.set_tracer(self.set_id, 3, sink);
+        self.outputs.4.set_tracer(self.set_id, 4, sink);
+    }
+
     fn connect(&mut self, id: usize, in_handle: InHandle) -> Result<(), Error> {
         match id {
             0 => self.outputs.0.connect_any(in_handle),
@@ -255,24 +748,90 @@ impl<T1: Event, T2: Event, T3: Event, T4: Event, T5: Event> OutputSet
             2 => self.outputs.2.connect_any(in_handle),
             3 => self.outputs.3.connect_any(in_handle),
             4 => self.outputs.4.connect_any(in_handle),
-            _ => panic!("Invalid id, OutputSet has no such pin"),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
+        }
+    }
+
+    fn disconnect(&mut self, id: usize, filter: &mut dyn FnMut(&InHandle) -> bool) -> Result<Vec<InHandle>, Error> {
+        match id {
+            0 => Ok(self.outputs.0.disconnect_any(filter)),
+            1 => Ok(self.outputs.1.disconnect_any(filter)),
+            2 => Ok(self.outputs.2.disconnect_any(filter)),
+            3 => Ok(self.outputs.3.disconnect_any(filter)),
+            4 => Ok(self.outputs.4.disconnect_any(filter)),
+            _ => Err(Error::PinNotFound {
+                set: self.set_id.into(),
+                index: id,
+            }),
         }
     }
+
+    fn disconnect_all(&mut self, filter: &mut dyn FnMut(&InHandle) -> bool) -> Vec<(usize, InHandle)> {
+        let mut removed = Vec::new();
+        removed.extend(
+            self.outputs
+                .0
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (0, handle)),
+        );
+        removed.extend(
+            self.outputs
+                .1
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (1, handle)),
+        );
+        removed.extend(
+            self.outputs
+                .2
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (2, handle)),
+        );
+        removed.extend(
+            self.outputs
+                .3
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (3, handle)),
+        );
+        removed.extend(
+            self.outputs
+                .4
+                .disconnect_any(filter)
+                .into_iter()
+                .map(|handle| (4, handle)),
+        );
+        removed
+    }
 }
 
 /// Type erased handle to an output in an output set.
 #[derive(Clone)]
 pub struct OutHandle {
     set_id: OutputSetId,
+    owner: SetOwner,
     event_type: TypeId,
+    event_type_name: &'static str,
     pin_id: usize,
 }
 
 impl OutHandle {
-    pub fn new<O: OutputSet>(output_set: &Rc<RefCell<O>>, pin_id: usize, event_type: TypeId) -> Self {
+    pub fn new<O: OutputSet>(
+        output_set: &Rc<RefCell<O>>,
+        pin_id: usize,
+        event_type: TypeId,
+        event_type_name: &'static str,
+    ) -> Self {
         Self {
             set_id: output_set.borrow().id(),
+            owner: output_set.borrow().owner().clone(),
             event_type,
+            event_type_name,
             pin_id,
         }
     }
@@ -281,13 +840,31 @@ impl OutHandle {
         self.event_type
     }
 
-    pub(in crate::frp) fn set_id(&self) -> OutputSetId {
+    pub fn event_type_name(&self) -> &'static str {
+        self.event_type_name
+    }
+
+    /// Get the system the output set of the handle was added to.
+    pub fn system_id(&self) -> Option<SystemId> {
+        self.owner.get()
+    }
+
+    pub fn set_id(&self) -> OutputSetId {
         self.set_id
     }
 
-    pub(in crate::frp) fn pin_id(&self) -> usize {
+    pub fn pin_id(&self) -> usize {
         self.pin_id
     }
+
+    /// Get the identity and the event type of the pin.
+    pub fn pin_info(&self) -> PinInfo {
+        PinInfo {
+            set: self.set_id.into(),
+            pin_id: self.pin_id,
+            event_type_name: self.event_type_name,
+        }
+    }
 }
 
 /// Handle to an output in an output set.
@@ -299,14 +876,17 @@ pub struct TypedOutHandle<T: Event> {
 
 impl<T: Event> TypedOutHandle<T> {
     pub fn new<O: OutputSet>(output_set: &Rc<RefCell<O>>, pin_id: usize) -> Self {
-        Self::from(OutHandle::new(output_set, pin_id, TypeId::of::<T>()))
+        Self {
+            handle: OutHandle::new(output_set, pin_id, TypeId::of::<T>(), any::type_name::<T>()),
+            ph: PhantomData,
+        }
     }
 
-    pub(in crate::frp) fn set_id(&self) -> OutputSetId {
+    pub fn set_id(&self) -> OutputSetId {
         self.handle.set_id()
     }
 
-    pub(in crate::frp) fn pin_id(&self) -> usize {
+    pub fn pin_id(&self) -> usize {
         self.handle.pin_id()
     }
 
@@ -315,15 +895,21 @@ impl<T: Event> TypedOutHandle<T> {
     }
 }
 
-impl<T: Event> From<OutHandle> for TypedOutHandle<T> {
-    /// Convert from a type erase handle.
-    /// #Panic
-    /// This function may panic if the types are not matching.
-    fn from(handle: OutHandle) -> Self {
-        assert_eq!(handle.event_type, TypeId::of::<T>());
-        Self {
-            handle,
-            ph: PhantomData,
+impl<T: Event> TryFrom<OutHandle> for TypedOutHandle<T> {
+    type Error = Error;
+
+    /// Convert from a type erased handle. If the types are not matching, an error is returned.
+    fn try_from(handle: OutHandle) -> Result<Self, Self::Error> {
+        if handle.event_type == TypeId::of::<T>() {
+            Ok(Self {
+                handle,
+                ph: PhantomData,
+            })
+        } else {
+            Err(Error::TypeMismatch {
+                expected: any::type_name::<T>(),
+                found: handle.event_type_name,
+            })
         }
     }
 }
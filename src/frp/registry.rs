@@ -0,0 +1,125 @@
+use crate::frp::{Error, Event, InHandle, IntoBehaviourNode, NodeId, OutHandle, System, TypedInHandle, TypedOutHandle};
+use serde::de::DeserializeOwned;
+use std::{
+    any::{self, TypeId},
+    collections::HashMap,
+};
+
+type BehaviourFactory = Box<dyn Fn(&mut System, ron::Value) -> Result<NodeId, Error>>;
+
+/// Type erased operations of a registered event type.
+struct EventFactory {
+    type_id: TypeId,
+    create_input: fn(&mut System) -> OutHandle,
+    create_output: fn(&mut System) -> InHandle,
+    connect_delayed: fn(&mut System, &OutHandle, &InHandle) -> Result<(), Error>,
+}
+
+/// Map the names used in a `SystemDescription` to the behaviours and event types.
+#[derive(Default)]
+pub struct BehaviourRegistry {
+    behaviours: HashMap<String, BehaviourFactory>,
+    events: HashMap<String, EventFactory>,
+}
+
+impl BehaviourRegistry {
+    /// Register a behaviour. The parameters of the description are deserialized into `B` to create the behaviour.
+    /// Each name can be registered only once.
+    pub fn add_behaviour<B>(&mut self, name: &str) -> Result<&mut Self, Error>
+    where
+        B: IntoBehaviourNode + DeserializeOwned,
+    {
+        if self.behaviours.contains_key(name) {
+            return Err(Error::DuplicateRegistration(name.to_string()));
+        }
+        let factory: BehaviourFactory = Box::new(|system, parameters| {
+            let behaviour: B = parameters
+                .into_rust()
+                .map_err(|err| Error::InvalidDescription(err.to_string()))?;
+            Ok(system.add_behaviour(behaviour)?.id())
+        });
+        self.behaviours.insert(name.to_string(), factory);
+        Ok(self)
+    }
+
+    /// Register an event type for the inputs and outputs of the system and the delayed connections.
+    /// Each name and event type can be registered only once.
+    pub fn add_event<T: Event>(&mut self, name: &str) -> Result<&mut Self, Error> {
+        if self.events.contains_key(name) {
+            return Err(Error::DuplicateRegistration(name.to_string()));
+        }
+        if self.events.values().any(|factory| factory.type_id == TypeId::of::<T>()) {
+            return Err(Error::DuplicateRegistration(any::type_name::<T>().to_string()));
+        }
+        let factory = EventFactory {
+            type_id: TypeId::of::<T>(),
+            create_input: |system| system.create_input::<T>().handle().clone(),
+            create_output: |system| system.create_output::<T>().handle().clone(),
+            connect_delayed: |system, pin_out, pin_in| {
+                system.connect_delayed::<T>(
+                    &TypedOutHandle::try_from(pin_out.clone())?,
+                    &TypedInHandle::try_from(pin_in.clone())?,
+                )
+            },
+        };
+        self.events.insert(name.to_string(), factory);
+        Ok(self)
+    }
+
+    pub(in crate::frp) fn create_behaviour(
+        &self,
+        system: &mut System,
+        behaviour: &str,
+        parameters: ron::Value,
+    ) -> Result<NodeId, Error> {
+        let factory = self
+            .behaviours
+            .get(behaviour)
+            .ok_or_else(|| Error::UnknownBehaviour(behaviour.to_string()))?;
+        factory(system, parameters)
+    }
+
+    pub(in crate::frp) fn create_input(&self, system: &mut System, event_type: &str) -> Result<OutHandle, Error> {
+        let factory = self.get_event(event_type)?;
+        Ok((factory.create_input)(system))
+    }
+
+    pub(in crate::frp) fn create_output(&self, system: &mut System, event_type: &str) -> Result<InHandle, Error> {
+        let factory = self.get_event(event_type)?;
+        Ok((factory.create_output)(system))
+    }
+
+    pub(in crate::frp) fn connect_delayed(
+        &self,
+        system: &mut System,
+        pin_out: &OutHandle,
+        pin_in: &InHandle,
+    ) -> Result<(), Error> {
+        if pin_out.event_type_id() != pin_in.event_type_id() {
+            return Err(Error::IncompatiblePinTypes {
+                output: pin_out.pin_info(),
+                input: pin_in.pin_info(),
+            });
+        }
+        let factory = self
+            .events
+            .values()
+            .find(|factory| factory.type_id == pin_out.event_type_id())
+            .ok_or_else(|| Error::UnknownEventType(pin_out.event_type_name().to_string()))?;
+        (factory.connect_delayed)(system, pin_out, pin_in)
+    }
+
+    /// Get the registered name of an event type.
+    pub(in crate::frp) fn event_type_name(&self, type_id: TypeId) -> Option<&str> {
+        self.events
+            .iter()
+            .find(|(_, factory)| factory.type_id == type_id)
+            .map(|(name, _)| name.as_str())
+    }
+
+    fn get_event(&self, event_type: &str) -> Result<&EventFactory, Error> {
+        self.events
+            .get(event_type)
+            .ok_or_else(|| Error::UnknownEventType(event_type.to_string()))
+    }
+}
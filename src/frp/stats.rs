@@ -0,0 +1,143 @@
+use crate::frp::{short_type_name, InputSetId, NodeId, OutputSet, OutputSetId};
+use std::{borrow::Cow, collections::HashMap, fmt::Write, time::Duration};
+
+/// Execution statistics of a behaviour node.
+#[derive(Clone, Debug, Default)]
+pub struct NodeStats {
+    /// Name of the type of the behaviour
+    pub name: String,
+    /// Number of the calls of the behaviour
+    pub calls: usize,
+    /// Time spent in the behaviour
+    pub behave_time: Duration,
+    /// Number of the received events by the input pin id
+    pub received: Vec<usize>,
+    /// Number of the emitted events by the output pin id
+    pub emitted: Vec<usize>,
+}
+
+/// Execution statistics of a system since the statistics were enabled or reset.
+#[derive(Clone, Debug, Default)]
+pub struct SystemStats {
+    /// Number of the runs of the system
+    pub runs: usize,
+    /// Time spent in the runs including the behaviours
+    pub run_time: Duration,
+    /// Statistics of the nodes in the execution order
+    pub nodes: Vec<(NodeId, NodeStats)>,
+}
+
+impl SystemStats {
+    /// Return a CSV summary with a line for each node. The event counts are listed by the pin ids.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("node,name,calls,behave_ns,received,emitted\n");
+        for (id, node) in &self.nodes {
+            let join = |counts: &[usize]| counts.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(";");
+            writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                id.index,
+                escape_csv(&node.name),
+                node.calls,
+                node.behave_time.as_nanos(),
+                join(&node.received),
+                join(&node.emitted)
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    /// Return the time spent in the nodes as folded stacks with nanosecond weights.
+    /// The result can be rendered by the usual flame graph tools (ex. inferno, flamegraph.pl).
+    pub fn to_folded(&self) -> String {
+        let mut folded = String::new();
+        let mut behave_time = Duration::ZERO;
+        for (id, node) in &self.nodes {
+            behave_time += node.behave_time;
+            writeln!(folded, "run;{}#{} {}", node.name, id.index, node.behave_time.as_nanos()).unwrap();
+        }
+        writeln!(folded, "run {}", self.run_time.saturating_sub(behave_time).as_nanos()).unwrap();
+        folded
+    }
+}
+
+/// Collect the statistics during the runs of a system.
+#[derive(Default)]
+pub(in crate::frp) struct StatsCollector {
+    pub runs: usize,
+    pub run_time: Duration,
+    /// Statistics by the node index
+    pub nodes: HashMap<usize, NodeStats>,
+    /// The number of the sent events of the output pins when they were last checked
+    last_sent: HashMap<(OutputSetId, usize), usize>,
+}
+
+impl StatsCollector {
+    /// Record a call of a behaviour.
+    pub fn record_behave(&mut self, node: usize, name: &str, time: Duration) {
+        let stats = self.nodes.entry(node).or_default();
+        if stats.name.is_empty() {
+            stats.name = short_type_name(name);
+        }
+        stats.calls += 1;
+        stats.behave_time += time;
+    }
+
+    /// Record the events sent on the outputs since the last check. The events are counted as received by the
+    /// nodes owning the connected inputs.
+    pub fn record_sent(
+        &mut self,
+        output_set: &dyn OutputSet,
+        owner: Option<usize>,
+        input_set_owners: &HashMap<InputSetId, usize>,
+    ) {
+        let set_id = output_set.id();
+        output_set.visit_sent(&mut |pin_id, sent, connections| {
+            let last_sent = self.last_sent.entry((set_id, pin_id)).or_insert(0);
+            let count = sent - *last_sent;
+            *last_sent = sent;
+            if count == 0 {
+                return;
+            }
+
+            if let Some(owner) = owner {
+                Self::add(&mut self.nodes.entry(owner).or_default().emitted, pin_id, count);
+            }
+            for connection in connections {
+                if let Some(&target) = input_set_owners.get(&connection.set_id()) {
+                    self.record_received(target, connection.pin_id(), count);
+                }
+            }
+        });
+    }
+
+    /// Record the events received by an input pin of a node.
+    pub fn record_received(&mut self, node: usize, pin_id: usize, count: usize) {
+        Self::add(&mut self.nodes.entry(node).or_default().received, pin_id, count);
+    }
+
+    /// Start counting the sent events of the outputs from their current state.
+    pub fn reset_sent(&mut self, output_set: &dyn OutputSet) {
+        let set_id = output_set.id();
+        output_set.visit_sent(&mut |pin_id, sent, _| {
+            self.last_sent.insert((set_id, pin_id), sent);
+        });
+    }
+
+    fn add(counts: &mut Vec<usize>, pin_id: usize, count: usize) {
+        if counts.len() <= pin_id {
+            counts.resize(pin_id + 1, 0);
+        }
+        counts[pin_id] += count;
+    }
+}
+
+/// Quote a CSV field containing a separator, a quote or a line break, the inner quotes are doubled (RFC 4180).
+fn escape_csv(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
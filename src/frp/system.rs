@@ -1,15 +1,24 @@
-use crate::frp::{
-    Behaviour, DynamicOutSet, Error, InHandle, InputSet, InputSetId, IntoBehaviourNode, OutHandle, OutputSet,
-    OutputSetId, TypedInHandle, TypedOutHandle,
+use crate::{
+    frp::{
+        short_type_name, Behaviour, BehaviourDescription, BehaviourRegistry, ConnectionDescription, DelayBuffer,
+        DelayedConnection, DynamicOutSet, Error, ErrorPolicy, GraphDetail, GraphEdge, GraphEdgeKind, GraphNode,
+        GraphNodeKind, InHandle, InputSet, InputSetId, IntoBehaviourNode, NodeError, NodePanic, OutHandle, OutPin,
+        OutputSet, OutputSetId, PinDescription, PinReference, StatsCollector, SystemDescription, SystemOutputSet,
+        SystemStats, TraceSink, Tracer, Transaction, TypedInHandle, TypedOutHandle, Worklist,
+    },
+    graph::{Edge, Graph, Node, TopologyOrder},
 };
 use std::{
-    cell::RefCell,
-    collections::HashMap,
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    mem,
+    ops::Deref,
+    panic::{self, AssertUnwindSafe},
     rc::{Rc, Weak},
-    sync::{
-        atomic::{self, AtomicUsize},
-        Arc,
-    },
+    sync::atomic::{self, AtomicUsize},
+    time::Instant,
 };
 
 use super::GeneralBehaviourNode;
@@ -17,24 +26,157 @@ use super::GeneralBehaviourNode;
 pub trait Event: 'static + Clone {}
 impl<T> Event for T where T: 'static + Clone {}
 
+/// Unique identifier of a system.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SystemId(usize);
+
+/// The system a set was added to, it is shared by the set and the handles to its pins.
+#[derive(Clone, Default)]
+pub struct SetOwner(Rc<Cell<Option<SystemId>>>);
+
+impl SetOwner {
+    pub fn get(&self) -> Option<SystemId> {
+        self.0.get()
+    }
+
+    pub(in crate::frp) fn set(&self, system: SystemId) {
+        self.0.set(Some(system));
+    }
+}
+
+/// Stable identifier of a behaviour node in a system.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NodeId {
+    system: SystemId,
+    pub(in crate::frp) index: usize,
+    generation: usize,
+}
+
+/// Handle to a behaviour node added to a system. It dereferences to the pin layout of the behaviour.
+pub struct NodeHandle<P> {
+    id: NodeId,
+    pins: P,
+}
+
+impl<P> NodeHandle<P> {
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    pub fn pins(&self) -> &P {
+        &self.pins
+    }
+
+    pub fn into_pins(self) -> P {
+        self.pins
+    }
+}
+
+impl<P> Deref for NodeHandle<P> {
+    type Target = P;
+
+    fn deref(&self) -> &Self::Target {
+        &self.pins
+    }
+}
+
+/// Connection or handle of a system that refers to a dropped input set, see `System::validate`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dangling {
+    /// Connection from an output pin to a pin of a dropped input set.
+    Connection {
+        source_set: OutputSetId,
+        source_pin: usize,
+        target_set: InputSetId,
+        target_pin: usize,
+    },
+    /// Delayed connection from an output pin to a pin of a dropped input set.
+    Delayed {
+        source_set: OutputSetId,
+        source_pin: usize,
+        target_set: InputSetId,
+        target_pin: usize,
+    },
+    /// The errors of the behaviours are routed to a pin of a dropped input set.
+    ErrorRoute { target_set: InputSetId, target_pin: usize },
+}
+
+/// Storage of a node, the slots of the removed nodes are reused with an increased generation.
+#[derive(Default)]
+struct NodeSlot {
+    generation: usize,
+    node: Option<Box<dyn GeneralBehaviourNode>>,
+    /// Description of the node if it was created from a registry
+    description: Option<BehaviourDescription>,
+    /// The node panicked and it is not executed anymore
+    quarantined: bool,
+}
+
 /// Store an FRP graph.
 pub struct System {
+    id: SystemId,
     /// input of the system that triggers the execution of the graph
     system_inputs: Rc<RefCell<DynamicOutSet>>,
     /// output of the system that can trigger the clients of the graph
-    //outputs: DynamicOutputSet,
+    system_outputs: Rc<RefCell<SystemOutputSet>>,
     /// References to all the `InputSet`s in this system
     input_set_references: HashMap<InputSetId, Weak<RefCell<dyn InputSet>>>,
     /// References to all the `OutputSet`s in this system
     output_set_references: HashMap<OutputSetId, Weak<RefCell<dyn OutputSet>>>,
-    nodes: Vec<Box<dyn GeneralBehaviourNode>>,
+    nodes: Vec<NodeSlot>,
+    /// Index of the unused slots in nodes
+    free_slots: Vec<usize>,
+    /// Index of the node owning an `InputSet`
+    input_set_owners: HashMap<InputSetId, usize>,
+    /// Index of the node owning an `OutputSet`
+    output_set_owners: HashMap<OutputSetId, usize>,
+    /// Connections between the nodes given by the node indices and the execution order of the nodes,
+    /// each node comes after all of its producers
+    topology: TopologyOrder,
+    /// Nodes connected to the inputs of the system by the pin id of the inputs
+    system_input_listeners: HashMap<usize, Vec<usize>>,
+    /// Connections that forward the events in the next run only
+    delayed_connections: Vec<Rc<RefCell<dyn DelayedConnection>>>,
+    /// Nodes with pending events during a run
+    worklist: Worklist,
+    /// Names of the inputs of the system by the pin id
+    input_names: HashMap<usize, String>,
+    /// Names of the outputs of the system by the pin id
+    output_names: HashMap<usize, String>,
+    /// Index of the nodes by the name given in their description
+    behaviour_names: HashMap<String, usize>,
+    /// Execution statistics if enabled
+    stats: Option<StatsCollector>,
+    /// Tracing of the deliveries, it is installed into the outputs only when a tracer is set
+    trace_sink: TraceSink,
+    tracing: bool,
+    /// Handling of the failed behaviours
+    error_policy: ErrorPolicy,
+    /// Errors routed to a node that does not come after the failed node, they are delivered in the next run
+    routed_errors: Vec<NodeError>,
+    /// Catch the panics of the behaviours
+    isolate_panics: bool,
+    /// The panics of the quarantined nodes
+    quarantine: Vec<NodePanic>,
 }
 
 impl Default for System {
     fn default() -> Self {
+        let id = SystemId(next_id());
+
         let system_inputs = Rc::new(RefCell::new(DynamicOutSet::default()));
+        system_inputs.borrow().owner().set(id);
 
-        let input_set_references: HashMap<InputSetId, Weak<RefCell<dyn InputSet>>> = HashMap::new();
+        let system_outputs = Rc::new(RefCell::new(SystemOutputSet::default()));
+        system_outputs.borrow().owner().set(id);
+
+        let input_set_references = {
+            let mut input_set_references: HashMap<InputSetId, Weak<RefCell<dyn InputSet>>> = HashMap::new();
+            let set_id = system_outputs.borrow().id();
+            let weak = Rc::downgrade(&system_outputs);
+            input_set_references.insert(set_id, weak);
+            input_set_references
+        };
         let output_set_references = {
             let mut output_set_references: HashMap<OutputSetId, Weak<RefCell<dyn OutputSet>>> = HashMap::new();
             let set_id = system_inputs.borrow().id();
@@ -44,35 +186,195 @@ impl Default for System {
         };
 
         Self {
+            id,
             system_inputs,
+            system_outputs,
             input_set_references,
             output_set_references,
             nodes: Vec::new(),
+            free_slots: Vec::new(),
+            input_set_owners: HashMap::new(),
+            output_set_owners: HashMap::new(),
+            topology: TopologyOrder::default(),
+            system_input_listeners: HashMap::new(),
+            delayed_connections: Vec::new(),
+            worklist: Worklist::default(),
+            input_names: HashMap::new(),
+            output_names: HashMap::new(),
+            behaviour_names: HashMap::new(),
+            stats: None,
+            trace_sink: TraceSink::default(),
+            tracing: false,
+            error_policy: ErrorPolicy::default(),
+            routed_errors: Vec::new(),
+            isolate_panics: false,
+            quarantine: Vec::new(),
         }
     }
 }
 
 impl System {
+    /// Get the identifier of the system, it is also available on the handles of the pins of the system.
+    pub fn id(&self) -> SystemId {
+        self.id
+    }
+
     /// Create a new input for the system.
     pub fn create_input<T: Event>(&mut self) -> TypedOutHandle<T> {
         let pin_id = {
             let inputs = &mut *self.system_inputs.borrow_mut();
-            inputs.add::<T>()
+            let pin_id = inputs.add::<T>();
+            if self.tracing {
+                inputs.set_tracer(Some(&self.trace_sink));
+            }
+            pin_id
         };
         TypedOutHandle::new(&self.system_inputs, pin_id)
     }
 
+    /// Create a new output for the system. The events sent to the output can be observed
+    /// by `last_output`, `drain_output` and `on_output`. At most 1024 events are kept for `drain_output`,
+    /// see `set_output_capacity`.
+    pub fn create_output<T: Event>(&mut self) -> TypedInHandle<T> {
+        let pin_id = {
+            let outputs = &mut *self.system_outputs.borrow_mut();
+            outputs.add::<T>()
+        };
+        TypedInHandle::new(&self.system_outputs, pin_id)
+    }
+
+    /// Get the last event received by an output of the system.
+    pub fn last_output<T: Event>(&self, output: &TypedInHandle<T>) -> Result<Option<T>, Error> {
+        self.check_system(output.handle().system_id())?;
+        let outputs = &mut *self.system_outputs.borrow_mut();
+        let output = outputs.get(output).ok_or(Error::OutputNotFound)?;
+        Ok(output.last().cloned())
+    }
+
+    /// Take all the events received by an output of the system since the last drain.
+    pub fn drain_output<T: Event>(&mut self, output: &TypedInHandle<T>) -> Result<Vec<T>, Error> {
+        self.check_system(output.handle().system_id())?;
+        let outputs = &mut *self.system_outputs.borrow_mut();
+        let output = outputs.get(output).ok_or(Error::OutputNotFound)?;
+        Ok(output.drain())
+    }
+
+    /// Set the number of the events kept for `drain_output`, above it the oldest events are dropped.
+    /// With zero capacity no event is kept, the output can be observed by `last_output` and `on_output` only.
+    pub fn set_output_capacity<T: Event>(&mut self, output: &TypedInHandle<T>, capacity: usize) -> Result<(), Error> {
+        self.check_system(output.handle().system_id())?;
+        let outputs = &mut *self.system_outputs.borrow_mut();
+        let output = outputs.get(output).ok_or(Error::OutputNotFound)?;
+        output.set_capacity(capacity);
+        Ok(())
+    }
+
+    /// Register a callback that is called with the events received by an output of the system after each run.
+    pub fn on_output<T: Event, F: FnMut(&T) + 'static>(
+        &mut self,
+        output: &TypedInHandle<T>,
+        callback: F,
+    ) -> Result<(), Error> {
+        self.check_system(output.handle().system_id())?;
+        let outputs = &mut *self.system_outputs.borrow_mut();
+        let output = outputs.get(output).ok_or(Error::OutputNotFound)?;
+        output.add_callback(Box::new(callback));
+        Ok(())
+    }
+
     /// Add a new behaviour to the system.
     pub fn add_behaviour<B: IntoBehaviourNode>(
         &mut self,
         behaviour: B,
-    ) -> Result<<B::Behaviour as Behaviour>::PinLayout, Error> {
+    ) -> Result<NodeHandle<<B::Behaviour as Behaviour>::PinLayout>, Error> {
         let behaviour = behaviour.into_behaviour_node()?;
+        if self.tracing {
+            behaviour.output_set.borrow_mut().set_tracer(Some(&self.trace_sink));
+        }
         self.add_input_set_reference(&behaviour.input_set);
         self.add_output_set_reference(&behaviour.output_set);
-        let pin_layout = behaviour.get_pins();
-        self.nodes.push(Box::new(behaviour));
-        Ok(pin_layout)
+        let pins = behaviour.get_pins();
+
+        let index = self.free_slots.pop().unwrap_or_else(|| {
+            self.nodes.push(NodeSlot::default());
+            self.nodes.len() - 1
+        });
+        self.input_set_owners.insert(behaviour.input_set_id(), index);
+        self.output_set_owners.insert(behaviour.output_set_id(), index);
+        let slot = &mut self.nodes[index];
+        slot.node = Some(Box::new(behaviour));
+        self.topology.add_node(index);
+
+        let id = NodeId {
+            system: self.id,
+            index,
+            generation: slot.generation,
+        };
+        Ok(NodeHandle { id, pins })
+    }
+
+    /// Remove a behaviour from the system. All the connections of the node are removed and the pin handles
+    /// of the node become invalid.
+    pub fn remove_behaviour(&mut self, id: NodeId) -> Result<(), Error> {
+        self.check_system(Some(id.system))?;
+        let node = match self.nodes.get_mut(id.index) {
+            Some(slot) if slot.generation == id.generation => slot.node.take().ok_or(Error::NodeNotFound)?,
+            _ => return Err(Error::NodeNotFound),
+        };
+        self.nodes[id.index].generation += 1;
+        if let Some(description) = self.nodes[id.index].description.take() {
+            self.behaviour_names.remove(&description.name);
+        }
+        self.nodes[id.index].quarantined = false;
+        self.quarantine.retain(|panic| panic.node != id);
+        self.free_slots.push(id.index);
+
+        let input_set_id = node.input_set_id();
+        let output_set_id = node.output_set_id();
+
+        // remove the listeners pointing to the node from the producers
+        let mut producers = vec![self.system_inputs.borrow().id()];
+        for &predecessor in self.topology.predecessors(id.index) {
+            // NO-PANIC: all the nodes of the topology are alive
+            producers.push(self.nodes[predecessor].node.as_ref().unwrap().output_set_id());
+        }
+        producers.sort_unstable();
+        producers.dedup();
+        for producer in producers {
+            if let Ok(output_set) = self.get_output_set(producer) {
+                output_set
+                    .borrow_mut()
+                    .disconnect_all(&mut |handle| handle.set_id() == input_set_id);
+            }
+        }
+        for listeners in self.system_input_listeners.values_mut() {
+            listeners.retain(|listener| *listener != id.index);
+        }
+
+        // delayed connections from the node are dropped with its outputs, to the node are disconnected
+        let mut delayed_connections = mem::take(&mut self.delayed_connections);
+        delayed_connections.retain(|delayed| {
+            let delayed = &*delayed.borrow();
+            if delayed.source().set_id() == output_set_id {
+                false
+            } else if delayed.target().set_id() == input_set_id {
+                self.detach_delayed(delayed);
+                false
+            } else {
+                true
+            }
+        });
+        self.delayed_connections = delayed_connections;
+
+        if let Some(stats) = &mut self.stats {
+            stats.nodes.remove(&id.index);
+        }
+        self.topology.remove_node(id.index);
+        self.input_set_references.remove(&input_set_id);
+        self.output_set_references.remove(&output_set_id);
+        self.input_set_owners.remove(&input_set_id);
+        self.output_set_owners.remove(&output_set_id);
+        Ok(())
     }
 
     /// Try to connect the output and input, see `connect_any`
@@ -83,53 +385,996 @@ impl System {
     /// Try to connect the output and input.
     /// The operation fails if either the type of the input and output are not matching ot the connection would create a cycle in the graph.
     pub fn connect_any(&mut self, pin_out: &OutHandle, pin_in: &InHandle) -> Result<(), Error> {
+        self.check_system(pin_out.system_id())?;
+        self.check_system(pin_in.system_id())?;
         if pin_out.event_type_id() != pin_in.event_type_id() {
-            Err(Error::IncompatiblePinTypes)
+            Err(Error::IncompatiblePinTypes {
+                output: pin_out.pin_info(),
+                input: pin_in.pin_info(),
+            })
+        } else if !self.input_set_references.contains_key(&pin_in.set_id()) {
+            Err(Error::InputNotFound)
+        } else {
+            let out_set = self.get_output_set(pin_out.set_id())?;
+
+            // system inputs are not part of the ordering as they are always triggered first
+            let from = self.output_set_owners.get(&pin_out.set_id());
+            let to = self.input_set_owners.get(&pin_in.set_id());
+            let edge = match (from, to) {
+                (Some(&from), Some(&to)) => Some((from, to)),
+                _ => None,
+            };
+
+            // update the order before the connection is made to keep the system unchanged on cycle
+            if let Some((from, to)) = edge {
+                if !self.topology.add_edge(from, to) {
+                    return Err(Error::Cycle);
+                }
+            }
+
+            if let Err(err) = out_set.borrow_mut().connect(pin_out.pin_id(), pin_in.clone()) {
+                if let Some((from, to)) = edge {
+                    self.topology.remove_edge(from, to);
+                }
+                return Err(err);
+            }
+
+            if let Some(&to) = to {
+                if pin_out.set_id() == self.system_inputs.borrow().id() {
+                    self.system_input_listeners
+                        .entry(pin_out.pin_id())
+                        .or_default()
+                        .push(to);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Try to connect the output and input with a delay. The events sent on the output are delivered to the
+    /// input only at the start of the next `run_on`. Delayed connections are ignored by the cycle detection,
+    /// thus they can be used to create feedback loops.
+    pub fn connect_delayed<T: Event>(
+        &mut self,
+        pin_out: &TypedOutHandle<T>,
+        pin_in: &TypedInHandle<T>,
+    ) -> Result<(), Error> {
+        self.check_system(pin_out.handle().system_id())?;
+        self.check_system(pin_in.handle().system_id())?;
+        if !self.input_set_references.contains_key(&pin_in.handle().set_id()) {
+            return Err(Error::InputNotFound);
+        }
+        pin_in.handle().validate()?;
+        let out_set = self.get_output_set(pin_out.set_id())?;
+
+        let buffer = Rc::new(RefCell::new(DelayBuffer::new(pin_out.handle().clone(), pin_in.clone())));
+        if self.tracing {
+            buffer.borrow_mut().set_tracer(Some(&self.trace_sink));
+        }
+        let handle = TypedInHandle::<T>::new(&buffer, 0);
+        out_set
+            .borrow_mut()
+            .connect(pin_out.pin_id(), handle.handle().clone().into_delayed())?;
+        self.delayed_connections.push(buffer);
+        Ok(())
+    }
+
+    /// Remove a connection between the output and input, see `disconnect_any`
+    pub fn disconnect<T: Event>(
+        &mut self,
+        pin_out: &TypedOutHandle<T>,
+        pin_in: &TypedInHandle<T>,
+    ) -> Result<(), Error> {
+        self.disconnect_any(pin_out.handle(), pin_in.handle())
+    }
+
+    /// Remove a connection between the output and input. If the pins are connected multiple times, only one of the
+    /// connections is removed.
+    pub fn disconnect_any(&mut self, pin_out: &OutHandle, pin_in: &InHandle) -> Result<(), Error> {
+        self.check_system(pin_out.system_id())?;
+        self.check_system(pin_in.system_id())?;
+        let out_set = self.get_output_set(pin_out.set_id())?;
+        let mut found = false;
+        let removed = out_set.borrow_mut().disconnect(pin_out.pin_id(), &mut |handle| {
+            let selected = !found && handle.is_same_pin(pin_in);
+            found |= selected;
+            selected
+        })?;
+
+        if removed.is_empty() {
+            Err(Error::ConnectionNotFound)
         } else {
-            // todo: create topolgy ordering with cycle detection
-            // todo2: make update inceremntal, see: https://www.researchgate.net/publication/47841865_Maintaining_Longest_Paths_Incrementally            
-
-            let out_set = Arc::new(
-                self.output_set_references
-                    .get(&pin_out.set_id())
-                    .ok_or(Error::OutputNotFound)?,
-            )
-            .upgrade()
-            .unwrap();
-            out_set.borrow_mut().connect(pin_out.pin_id(), pin_in.clone())?;
+            self.forget_connection(pin_out.set_id(), pin_out.pin_id(), pin_in);
             Ok(())
         }
     }
 
+    /// Remove a delayed connection between the output and input, the events not delivered yet are dropped.
+    /// If the pins are connected multiple times, only one of the connections is removed.
+    pub fn disconnect_delayed<T: Event>(
+        &mut self,
+        pin_out: &TypedOutHandle<T>,
+        pin_in: &TypedInHandle<T>,
+    ) -> Result<(), Error> {
+        self.check_system(pin_out.handle().system_id())?;
+        self.check_system(pin_in.handle().system_id())?;
+        let position = self
+            .delayed_connections
+            .iter()
+            .position(|delayed| {
+                let delayed = delayed.borrow();
+                delayed.source().set_id() == pin_out.set_id()
+                    && delayed.source().pin_id() == pin_out.pin_id()
+                    && delayed.target().is_same_pin(pin_in.handle())
+            })
+            .ok_or(Error::ConnectionNotFound)?;
+        let delayed = self.delayed_connections.remove(position);
+        self.detach_delayed(&*delayed.borrow());
+        Ok(())
+    }
+
+    /// Remove all the connections of an output including the delayed connections.
+    pub fn disconnect_output(&mut self, pin_out: &OutHandle) -> Result<(), Error> {
+        self.check_system(pin_out.system_id())?;
+        let out_set = self.get_output_set(pin_out.set_id())?;
+        let removed = out_set.borrow_mut().disconnect(pin_out.pin_id(), &mut |_| true)?;
+        for handle in &removed {
+            self.forget_connection(pin_out.set_id(), pin_out.pin_id(), handle);
+        }
+        self.delayed_connections.retain(|delayed| {
+            let id = delayed.borrow().id();
+            !removed.iter().any(|handle| handle.set_id() == id)
+        });
+        Ok(())
+    }
+
+    /// Remove all the connections of an input including the delayed connections.
+    pub fn disconnect_input(&mut self, pin_in: &InHandle) -> Result<(), Error> {
+        self.check_system(pin_in.system_id())?;
+        if !self.input_set_references.contains_key(&pin_in.set_id()) {
+            return Err(Error::InputNotFound);
+        }
+
+        // for the inputs outside of the topology (ex. outputs of the system) all the outputs are checked
+        let mut producers = vec![self.system_inputs.borrow().id()];
+        if let Some(&node) = self.input_set_owners.get(&pin_in.set_id()) {
+            for &predecessor in self.topology.predecessors(node) {
+                // NO-PANIC: all the nodes of the topology are alive
+                producers.push(self.nodes[predecessor].node.as_ref().unwrap().output_set_id());
+            }
+            producers.sort_unstable();
+            producers.dedup();
+        } else {
+            producers = self.output_set_references.keys().copied().collect();
+        }
+
+        for producer in producers {
+            if let Ok(output_set) = self.get_output_set(producer) {
+                let removed = output_set
+                    .borrow_mut()
+                    .disconnect_all(&mut |handle| handle.is_same_pin(pin_in));
+                for (pin_id, handle) in removed {
+                    self.forget_connection(producer, pin_id, &handle);
+                }
+            }
+        }
+
+        let mut delayed_connections = mem::take(&mut self.delayed_connections);
+        delayed_connections.retain(|delayed| {
+            let delayed = &*delayed.borrow();
+            if delayed.target().is_same_pin(pin_in) {
+                self.detach_delayed(delayed);
+                false
+            } else {
+                true
+            }
+        });
+        self.delayed_connections = delayed_connections;
+        Ok(())
+    }
+
     /// Send an event to an input of the system and run the graph to completion.
-    /// #Panic
-    /// This function may panic if the input handle is not an input of the system.
+    /// The events of the delayed connections from the previous run are delivered before the event.
     pub fn run_on<T: Event>(&mut self, input: TypedOutHandle<T>, event: &T) -> Result<(), Error> {
+        let mut transaction = Transaction::default();
+        transaction.send(&input, event.clone());
+        self.commit(transaction)
+    }
+
+    /// Send all the events of the transaction to the inputs of the system and run the graph to completion.
+    /// The events of the delayed connections from the previous run are delivered before the events.
+    /// If any of the inputs is not an input of the system, no event is sent and an error is returned.
+    pub fn commit(&mut self, transaction: Transaction) -> Result<(), Error> {
+        for (input, _) in &transaction.events {
+            self.check_system(input.system_id())?;
+        }
+
+        let mut triggered = Vec::new();
         {
             let inputs = &mut *self.system_inputs.borrow_mut();
-            let input = inputs.get(input).ok_or(Error::InputNotFound)?;
-            input.send(event);
+            if transaction
+                .events
+                .iter()
+                .any(|(input, _)| input.set_id() != inputs.id() || !inputs.contains(input.pin_id()))
+            {
+                return Err(Error::InputNotFound);
+            }
+
+            for delayed in &self.delayed_connections {
+                let delayed = &mut *delayed.borrow_mut();
+                let forwarded = delayed.flush();
+                if forwarded > 0 {
+                    if let Some(&target) = self.input_set_owners.get(&delayed.target().set_id()) {
+                        triggered.push(target);
+                        if let Some(stats) = &mut self.stats {
+                            stats.record_received(target, delayed.target().pin_id(), forwarded);
+                        }
+                    }
+                }
+            }
+
+            let routed_errors = mem::take(&mut self.routed_errors);
+            if let ErrorPolicy::Route(pin_in) = &self.error_policy {
+                for error in &routed_errors {
+                    if !pin_in.push(error) {
+                        log::warn!("{} (the input of the error route was dropped)", error);
+                    }
+                }
+                if !routed_errors.is_empty() {
+                    if let Some(&target) = self.input_set_owners.get(&pin_in.handle().set_id()) {
+                        triggered.push(target);
+                    }
+                }
+            }
+
+            for (input, event) in &transaction.events {
+                inputs.send_any(input.pin_id(), event.as_ref())?;
+                triggered.extend(self.system_input_listeners.get(&input.pin_id()).into_iter().flatten());
+            }
+            if let Some(stats) = &mut self.stats {
+                stats.record_sent(inputs, None, &self.input_set_owners);
+            }
+        }
+
+        let started = Instant::now();
+        let result = self.run(triggered);
+        if let Some(stats) = &mut self.stats {
+            stats.runs += 1;
+            stats.run_time += started.elapsed();
+        }
+        self.system_outputs.borrow_mut().notify();
+        result
+    }
+
+    /// List the connections and handles that refer to dropped input sets. The connections are removed
+    /// from the outputs on the next event sent, the delayed connections and the error route remain until
+    /// they are disconnected or replaced.
+    pub fn validate(&self) -> Vec<Dangling> {
+        let mut dangling = Vec::new();
+        for output_set in self.output_set_references.values().filter_map(|weak| weak.upgrade()) {
+            let output_set = output_set.borrow();
+            for pin in output_set.pins() {
+                for connection in pin.connections {
+                    if !connection.is_alive() {
+                        dangling.push(Dangling::Connection {
+                            source_set: output_set.id(),
+                            source_pin: pin.pin_id,
+                            target_set: connection.set_id(),
+                            target_pin: connection.pin_id(),
+                        });
+                    }
+                }
+            }
+        }
+
+        for delayed in &self.delayed_connections {
+            let delayed = delayed.borrow();
+            if !delayed.target().is_alive() {
+                dangling.push(Dangling::Delayed {
+                    source_set: delayed.source().set_id(),
+                    source_pin: delayed.source().pin_id(),
+                    target_set: delayed.target().set_id(),
+                    target_pin: delayed.target().pin_id(),
+                });
+            }
+        }
+
+        if let ErrorPolicy::Route(pin_in) = &self.error_policy {
+            if !pin_in.handle().is_alive() {
+                dangling.push(Dangling::ErrorRoute {
+                    target_set: pin_in.handle().set_id(),
+                    target_pin: pin_in.handle().pin_id(),
+                });
+            }
         }
-        self.run();
+
+        dangling
+    }
+
+    /// Set the handling of the failed behaviours, the default policy is `ErrorPolicy::Abort`.
+    /// The input of a routing policy shall be a valid pin not belonging to another system.
+    pub fn set_error_policy(&mut self, policy: ErrorPolicy) -> Result<(), Error> {
+        if let ErrorPolicy::Route(pin_in) = &policy {
+            self.check_system(pin_in.handle().system_id())?;
+            pin_in.handle().validate()?;
+        }
+        self.error_policy = policy;
+        Ok(())
+    }
+
+    /// Enable or disable the isolation of the panics of the behaviours. When enabled, a panicking node is
+    /// quarantined: it is not executed anymore, but the rest of the graph keeps running.
+    pub fn isolate_panics(&mut self, enable: bool) {
+        self.isolate_panics = enable;
+    }
+
+    /// Return the panics of the quarantined nodes.
+    pub fn quarantined(&self) -> &[NodePanic] {
+        &self.quarantine
+    }
+
+    /// Enable the execution of a quarantined node again.
+    pub fn release(&mut self, id: NodeId) -> Result<(), Error> {
+        self.check_system(Some(id.system))?;
+        let position = self
+            .quarantine
+            .iter()
+            .position(|panic| panic.node == id)
+            .ok_or(Error::NodeNotFound)?;
+        self.quarantine.remove(position);
+        self.nodes[id.index].quarantined = false;
         Ok(())
     }
 
+    /// Add a new behaviour to the system created from the description by a registry.
+    pub fn add_described_behaviour(
+        &mut self,
+        registry: &BehaviourRegistry,
+        description: BehaviourDescription,
+    ) -> Result<NodeId, Error> {
+        if self.behaviour_names.contains_key(&description.name) {
+            return Err(Error::InvalidDescription(format!(
+                "duplicate behaviour name {}",
+                description.name
+            )));
+        }
+        let id = registry.create_behaviour(self, &description.behaviour, description.parameters.clone())?;
+        self.behaviour_names.insert(description.name.clone(), id.index);
+        self.nodes[id.index].description = Some(description);
+        Ok(id)
+    }
+
+    /// Find an input of the system by the name given in the description.
+    pub fn find_input(&self, name: &str) -> Option<OutHandle> {
+        let (&pin_id, _) = self.input_names.iter().find(|(_, input)| *input == name)?;
+        let pin = self
+            .system_inputs
+            .borrow()
+            .pins()
+            .into_iter()
+            .find(|pin| pin.pin_id == pin_id)?;
+        Some(OutHandle::new(
+            &self.system_inputs,
+            pin_id,
+            pin.event_type_id,
+            pin.event_type_name,
+        ))
+    }
+
+    /// Find an output of the system by the name given in the description.
+    pub fn find_output(&self, name: &str) -> Option<InHandle> {
+        let (&pin_id, _) = self.output_names.iter().find(|(_, output)| *output == name)?;
+        let pin = self
+            .system_outputs
+            .borrow()
+            .pins()
+            .into_iter()
+            .find(|pin| pin.pin_id == pin_id)?;
+        Some(InHandle::new(
+            &self.system_outputs,
+            pin_id,
+            pin.event_type_id,
+            pin.event_type_name,
+        ))
+    }
+
+    /// Find a behaviour by the name given in the description.
+    pub fn find_behaviour(&self, name: &str) -> Option<NodeId> {
+        let &index = self.behaviour_names.get(name)?;
+        Some(NodeId {
+            system: self.id,
+            index,
+            generation: self.nodes[index].generation,
+        })
+    }
+
+    /// Get the handle of an input pin of a behaviour.
+    pub fn input_pin(&self, node: NodeId, pin_id: usize) -> Result<InHandle, Error> {
+        self.get_node(node)?.input_handle(pin_id).ok_or(Error::InputNotFound)
+    }
+
+    /// Get the handle of an output pin of a behaviour.
+    pub fn output_pin(&self, node: NodeId, pin_id: usize) -> Result<OutHandle, Error> {
+        self.get_node(node)?.output_handle(pin_id).ok_or(Error::OutputNotFound)
+    }
+
+    /// Create a system from a description. The behaviours and event types are created by the registry.
+    pub fn load(description: &SystemDescription, registry: &BehaviourRegistry) -> Result<System, Error> {
+        let mut system = System::default();
+        for input in &description.inputs {
+            if system.find_input(&input.name).is_some() {
+                return Err(Error::InvalidDescription(format!(
+                    "duplicate input name {}",
+                    input.name
+                )));
+            }
+            let handle = registry.create_input(&mut system, &input.event_type)?;
+            system.input_names.insert(handle.pin_id(), input.name.clone());
+        }
+        for output in &description.outputs {
+            if system.find_output(&output.name).is_some() {
+                return Err(Error::InvalidDescription(format!(
+                    "duplicate output name {}",
+                    output.name
+                )));
+            }
+            let handle = registry.create_output(&mut system, &output.event_type)?;
+            system.output_names.insert(handle.pin_id(), output.name.clone());
+        }
+        for behaviour in &description.behaviours {
+            system.add_described_behaviour(registry, behaviour.clone())?;
+        }
+
+        for connection in &description.connections {
+            let pin_out = match &connection.from {
+                PinReference::System(name) => system
+                    .find_input(name)
+                    .ok_or_else(|| Error::NameNotFound(name.clone()))?,
+                PinReference::Behaviour(name, pin_id) => {
+                    let node = system
+                        .find_behaviour(name)
+                        .ok_or_else(|| Error::NameNotFound(name.clone()))?;
+                    system.output_pin(node, *pin_id)?
+                }
+            };
+            let pin_in = match &connection.to {
+                PinReference::System(name) => system
+                    .find_output(name)
+                    .ok_or_else(|| Error::NameNotFound(name.clone()))?,
+                PinReference::Behaviour(name, pin_id) => {
+                    let node = system
+                        .find_behaviour(name)
+                        .ok_or_else(|| Error::NameNotFound(name.clone()))?;
+                    system.input_pin(node, *pin_id)?
+                }
+            };
+
+            if connection.delayed {
+                registry.connect_delayed(&mut system, &pin_out, &pin_in)?;
+            } else {
+                system.connect_any(&pin_out, &pin_in)?;
+            }
+        }
+        Ok(system)
+    }
+
+    /// Create the description of the system. All the behaviours have to be added by `add_described_behaviour`
+    /// and all the event types of the inputs and outputs of the system have to be registered.
+    /// The inputs and outputs without a name are named by their pin id, if such a name is already given to another
+    /// input or output, an error is returned.
+    pub fn save(&self, registry: &BehaviourRegistry) -> Result<SystemDescription, Error> {
+        let event_type = |type_id, type_name: &str| {
+            registry
+                .event_type_name(type_id)
+                .map(|name| name.to_string())
+                .ok_or_else(|| Error::UnknownEventType(type_name.to_string()))
+        };
+        let input_name = |pin_id| {
+            self.input_names
+                .get(&pin_id)
+                .cloned()
+                .unwrap_or_else(|| format!("input_{}", pin_id))
+        };
+        let output_name = |pin_id| {
+            self.output_names
+                .get(&pin_id)
+                .cloned()
+                .unwrap_or_else(|| format!("output_{}", pin_id))
+        };
+
+        let mut description = SystemDescription::default();
+        let mut outputs: Vec<(Option<&str>, Vec<OutPin>)> = Vec::new();
+
+        let input_pins = self.system_inputs.borrow().pins();
+        for pin in &input_pins {
+            description.inputs.push(PinDescription {
+                name: input_name(pin.pin_id),
+                event_type: event_type(pin.event_type_id, pin.event_type_name)?,
+            });
+        }
+        outputs.push((None, input_pins));
+
+        for pin in self.system_outputs.borrow().pins() {
+            description.outputs.push(PinDescription {
+                name: output_name(pin.pin_id),
+                event_type: event_type(pin.event_type_id, pin.event_type_name)?,
+            });
+        }
+        for (kind, pins) in [("input", &description.inputs), ("output", &description.outputs)] {
+            let mut names = HashSet::new();
+            if let Some(pin) = pins.iter().find(|pin| !names.insert(&pin.name)) {
+                return Err(Error::InvalidDescription(format!(
+                    "duplicate {} name {}",
+                    kind, pin.name
+                )));
+            }
+        }
+
+        for node in self.topology.iter() {
+            let slot = &self.nodes[node];
+            // NO-PANIC: all the nodes of the topology are alive
+            let behaviour = slot.node.as_ref().unwrap();
+            let behaviour_description = slot
+                .description
+                .as_ref()
+                .ok_or_else(|| Error::UnknownBehaviour(short_type_name(behaviour.name())))?;
+            description.behaviours.push(behaviour_description.clone());
+            let pins = self.get_output_set(behaviour.output_set_id())?.borrow().pins();
+            outputs.push((Some(&behaviour_description.name), pins));
+        }
+
+        let system_outputs_id = self.system_outputs.borrow().id();
+        let delayed_targets = self.delayed_targets();
+        for (source, pins) in outputs {
+            for pin in pins {
+                let from = match source {
+                    Some(name) => PinReference::Behaviour(name.to_string(), pin.pin_id),
+                    None => PinReference::System(input_name(pin.pin_id)),
+                };
+                for connection in pin.connections {
+                    let (target, delayed) = match delayed_targets.get(&connection.set_id()) {
+                        Some(target) => (target, true),
+                        None => (&connection, false),
+                    };
+                    let to = if target.set_id() == system_outputs_id {
+                        PinReference::System(output_name(target.pin_id()))
+                    } else {
+                        let description = self
+                            .input_set_owners
+                            .get(&target.set_id())
+                            .and_then(|&node| self.nodes[node].description.as_ref())
+                            .ok_or(Error::InputNotFound)?;
+                        PinReference::Behaviour(description.name.clone(), target.pin_id())
+                    };
+                    description.connections.push(ConnectionDescription {
+                        from: from.clone(),
+                        to,
+                        delayed,
+                    });
+                }
+            }
+        }
+
+        Ok(description)
+    }
+
+    /// Install a tracer that is called for each delivery of an event from an output to an input.
+    /// Without a tracer the deliveries are not traced and have no overhead.
+    pub fn set_tracer<T: Tracer + 'static>(&mut self, tracer: T) {
+        self.trace_sink.set_tracer(Some(Box::new(tracer)));
+        self.tracing = true;
+        self.install_tracer(Some(&self.trace_sink));
+    }
+
+    /// Remove the installed tracer.
+    pub fn remove_tracer(&mut self) {
+        self.trace_sink.set_tracer(None);
+        self.tracing = false;
+        self.install_tracer(None);
+    }
+
+    /// Register an event type to be rendered by its Debug implementation in the traced deliveries.
+    pub fn trace_debug<T: Event + Debug>(&mut self) {
+        self.trace_sink.add_debug::<T>();
+    }
+
+    /// Enable or disable the collection of the execution statistics. When enabled the calls of the behaviours
+    /// are timed and the events are counted on the pins which has some overhead on each run.
+    pub fn enable_stats(&mut self, enable: bool) {
+        if !enable {
+            self.stats = None;
+        } else if self.stats.is_none() {
+            self.stats = Some(self.new_stats());
+        }
+    }
+
+    /// Clear the collected statistics, if the statistics are disabled this function has no effect.
+    pub fn reset_stats(&mut self) {
+        if self.stats.is_some() {
+            self.stats = Some(self.new_stats());
+        }
+    }
+
+    /// Return the statistics collected since they were enabled or reset. If the statistics are disabled,
+    /// None is returned.
+    pub fn stats(&self) -> Option<SystemStats> {
+        let stats = self.stats.as_ref()?;
+        let nodes = self
+            .topology
+            .iter()
+            .map(|index| {
+                let slot = &self.nodes[index];
+                let id = NodeId {
+                    system: self.id,
+                    index,
+                    generation: slot.generation,
+                };
+                let mut node_stats = stats.nodes.get(&index).cloned().unwrap_or_default();
+                if node_stats.name.is_empty() {
+                    // NO-PANIC: all the nodes of the topology are alive
+                    node_stats.name = short_type_name(slot.node.as_ref().unwrap().name());
+                }
+                (id, node_stats)
+            })
+            .collect();
+        Some(SystemStats {
+            runs: stats.runs,
+            run_time: stats.run_time,
+            nodes,
+        })
+    }
+
+    /// Export the behaviours, the inputs and outputs of the system and the connections between them.
+    /// The behaviours are listed in the execution order.
+    pub fn get_graph(&self, detail: GraphDetail) -> Graph<GraphNode, GraphEdge> {
+        let mut graph = Graph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+        let pin_label = |prefix: &str, pin_id: usize, event_type_name: &str| match detail {
+            GraphDetail::Whole => format!("{} {}: {}", prefix, pin_id, short_type_name(event_type_name)),
+            _ => format!("{} {}", prefix, pin_id),
+        };
+        let add_node = |graph: &mut Graph<GraphNode, GraphEdge>, kind, label| {
+            graph.nodes.push(Node {
+                data: GraphNode { kind, label },
+            });
+            graph.nodes.len() - 1
+        };
+
+        // graph node of the output and input pins
+        let mut out_pins: HashMap<(OutputSetId, usize), usize> = HashMap::new();
+        let mut in_pins: HashMap<(InputSetId, usize), usize> = HashMap::new();
+        let mut outputs: Vec<(OutputSetId, Vec<OutPin>)> = Vec::new();
+
+        {
+            let system_inputs = self.system_inputs.borrow();
+            let pins = system_inputs.pins();
+            for pin in &pins {
+                let label = pin_label("input", pin.pin_id, pin.event_type_name);
+                let index = add_node(&mut graph, GraphNodeKind::SystemInput(pin.pin_id), label);
+                out_pins.insert((system_inputs.id(), pin.pin_id), index);
+            }
+            outputs.push((system_inputs.id(), pins));
+        }
+
+        for node in self.topology.iter() {
+            let slot = &self.nodes[node];
+            // NO-PANIC: all the nodes of the topology are alive
+            let behaviour = slot.node.as_ref().unwrap();
+            let id = NodeId {
+                system: self.id,
+                index: node,
+                generation: slot.generation,
+            };
+            let index = add_node(
+                &mut graph,
+                GraphNodeKind::Behaviour(id),
+                short_type_name(behaviour.name()),
+            );
+
+            if let Some(input_set) = self
+                .input_set_references
+                .get(&behaviour.input_set_id())
+                .and_then(|weak| weak.upgrade())
+            {
+                for pin in input_set.borrow().pins() {
+                    let pin_index = if detail == GraphDetail::Nodes {
+                        index
+                    } else {
+                        let label = pin_label("in", pin.pin_id, pin.event_type_name);
+                        let pin_index = add_node(&mut graph, GraphNodeKind::InputPin(id, pin.pin_id), label);
+                        graph.edges.push(Edge {
+                            data: GraphEdge {
+                                kind: GraphEdgeKind::Pin,
+                                label: None,
+                            },
+                            from: pin_index,
+                            to: index,
+                        });
+                        pin_index
+                    };
+                    in_pins.insert((behaviour.input_set_id(), pin.pin_id), pin_index);
+                }
+            }
+
+            if let Ok(output_set) = self.get_output_set(behaviour.output_set_id()) {
+                let pins = output_set.borrow().pins();
+                for pin in &pins {
+                    let pin_index = if detail == GraphDetail::Nodes {
+                        index
+                    } else {
+                        let label = pin_label("out", pin.pin_id, pin.event_type_name);
+                        let pin_index = add_node(&mut graph, GraphNodeKind::OutputPin(id, pin.pin_id), label);
+                        graph.edges.push(Edge {
+                            data: GraphEdge {
+                                kind: GraphEdgeKind::Pin,
+                                label: None,
+                            },
+                            from: index,
+                            to: pin_index,
+                        });
+                        pin_index
+                    };
+                    out_pins.insert((behaviour.output_set_id(), pin.pin_id), pin_index);
+                }
+                outputs.push((behaviour.output_set_id(), pins));
+            }
+        }
+
+        {
+            let system_outputs = self.system_outputs.borrow();
+            for pin in system_outputs.pins() {
+                let label = pin_label("output", pin.pin_id, pin.event_type_name);
+                let index = add_node(&mut graph, GraphNodeKind::SystemOutput(pin.pin_id), label);
+                in_pins.insert((system_outputs.id(), pin.pin_id), index);
+            }
+        }
+
+        let delayed_targets = self.delayed_targets();
+        for (set_id, pins) in outputs {
+            for pin in pins {
+                let from = out_pins[&(set_id, pin.pin_id)];
+                for connection in pin.connections {
+                    let (target, kind) = match delayed_targets.get(&connection.set_id()) {
+                        Some(target) => (target, GraphEdgeKind::Delayed),
+                        None => (&connection, GraphEdgeKind::Connection),
+                    };
+                    if let Some(&to) = in_pins.get(&(target.set_id(), target.pin_id())) {
+                        let label = match (detail, kind) {
+                            (GraphDetail::Whole, GraphEdgeKind::Delayed) => {
+                                Some(format!("delayed: {}", short_type_name(pin.event_type_name)))
+                            }
+                            (GraphDetail::Whole, _) => Some(short_type_name(pin.event_type_name)),
+                            (_, GraphEdgeKind::Delayed) => Some("delayed".to_string()),
+                            _ => None,
+                        };
+                        graph.edges.push(Edge {
+                            data: GraphEdge { kind, label },
+                            from,
+                            to,
+                        });
+                    }
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Return the graphviz compatible graph of the system, see `get_graph`.
+    pub fn get_dot_graph(&self, detail: GraphDetail) -> String {
+        self.get_graph(detail).dot_graph()
+    }
+
+    fn get_node(&self, id: NodeId) -> Result<&dyn GeneralBehaviourNode, Error> {
+        self.check_system(Some(id.system))?;
+        match self.nodes.get(id.index) {
+            Some(slot) if slot.generation == id.generation => slot.node.as_deref().ok_or(Error::NodeNotFound),
+            _ => Err(Error::NodeNotFound),
+        }
+    }
+
+    /// Check that a node or pin does not belong to another system.
+    fn check_system(&self, system: Option<SystemId>) -> Result<(), Error> {
+        match system {
+            Some(system) if system != self.id => Err(Error::ForeignHandle),
+            _ => Ok(()),
+        }
+    }
+
+    /// The inputs the buffers of the delayed connections forward the events to.
+    fn delayed_targets(&self) -> HashMap<InputSetId, InHandle> {
+        self.delayed_connections
+            .iter()
+            .map(|delayed| {
+                let delayed = delayed.borrow();
+                (delayed.id(), delayed.target().clone())
+            })
+            .collect()
+    }
+
+    fn install_tracer(&self, sink: Option<&TraceSink>) {
+        for output_set in self.output_set_references.values().filter_map(|weak| weak.upgrade()) {
+            output_set.borrow_mut().set_tracer(sink);
+        }
+        for delayed in &self.delayed_connections {
+            delayed.borrow_mut().set_tracer(sink);
+        }
+    }
+
+    /// Create an empty statistics counting the events from the current state of the outputs.
+    fn new_stats(&self) -> StatsCollector {
+        let mut stats = StatsCollector::default();
+        for output_set in self.output_set_references.values().filter_map(|weak| weak.upgrade()) {
+            stats.reset_sent(&*output_set.borrow());
+        }
+        stats
+    }
+
+    fn get_output_set(&self, set_id: OutputSetId) -> Result<Rc<RefCell<dyn OutputSet>>, Error> {
+        self.output_set_references
+            .get(&set_id)
+            .and_then(|weak| weak.upgrade())
+            .ok_or(Error::OutputNotFound)
+    }
+
+    /// Remove the buffer of a delayed connection from the listeners of its source.
+    fn detach_delayed(&self, delayed: &dyn DelayedConnection) {
+        if let Ok(output_set) = self.get_output_set(delayed.source().set_id()) {
+            let id = delayed.id();
+            let removed = output_set
+                .borrow_mut()
+                .disconnect(delayed.source().pin_id(), &mut |handle| handle.set_id() == id);
+            if let Err(err) = removed {
+                log::warn!("Failed to remove a delayed connection: {}", err);
+            }
+        }
+    }
+
+    /// Update the topology and the listeners of the system inputs after a connection was removed.
+    fn forget_connection(&mut self, out_set_id: OutputSetId, out_pin_id: usize, pin_in: &InHandle) {
+        if let Some(&to) = self.input_set_owners.get(&pin_in.set_id()) {
+            if let Some(&from) = self.output_set_owners.get(&out_set_id) {
+                self.topology.remove_edge(from, to);
+            } else if out_set_id == self.system_inputs.borrow().id() {
+                if let Some(listeners) = self.system_input_listeners.get_mut(&out_pin_id) {
+                    if let Some(i) = listeners.iter().position(|listener| *listener == to) {
+                        listeners.swap_remove(i);
+                    }
+                }
+            }
+        }
+    }
+
     fn add_input_set_reference<I: InputSet>(&mut self, input_set: &Rc<RefCell<I>>) {
+        input_set.borrow().owner().set(self.id);
         let set_id = input_set.borrow().id();
         let weak = Rc::downgrade(input_set);
         self.input_set_references.insert(set_id, weak);
     }
 
     fn add_output_set_reference<O: OutputSet>(&mut self, output_set: &Rc<RefCell<O>>) {
+        output_set.borrow().owner().set(self.id);
         let set_id = output_set.borrow().id();
         let weak = Rc::downgrade(output_set);
         self.output_set_references.insert(set_id, weak);
     }
 
-    fn run(&mut self) {
-        for node in &mut self.nodes {
-            node.process();
+    /// Process the triggered nodes and the nodes affected by them in topological order.
+    fn run(&mut self, triggered: Vec<usize>) -> Result<(), Error> {
+        for node in triggered {
+            if self.is_node_dirty(node) {
+                self.worklist.push(&self.topology, node);
+            }
         }
+
+        let isolate_panics = self.isolate_panics;
+        let process = |behaviour: &mut dyn GeneralBehaviourNode| {
+            if isolate_panics {
+                panic::catch_unwind(AssertUnwindSafe(|| behaviour.process()))
+            } else {
+                Ok(behaviour.process())
+            }
+        };
+
+        while let Some(node) = self.worklist.pop() {
+            let slot = &mut self.nodes[node];
+            // NO-PANIC: all the nodes of the topology are alive
+            let behaviour = slot.node.as_mut().unwrap();
+            let result = if let Some(stats) = &mut self.stats {
+                let started = Instant::now();
+                let result = process(behaviour.as_mut());
+                if !matches!(result, Ok(Ok(false))) {
+                    stats.record_behave(node, behaviour.name(), started.elapsed());
+                }
+                if let Some(output_set) = self
+                    .output_set_references
+                    .get(&behaviour.output_set_id())
+                    .and_then(|weak| weak.upgrade())
+                {
+                    stats.record_sent(&*output_set.borrow(), Some(node), &self.input_set_owners);
+                }
+                result
+            } else {
+                process(behaviour.as_mut())
+            };
+
+            // the borrows of the sets are released during the unwinding, thus only the node is disabled
+            let result = result.unwrap_or_else(|payload| {
+                let panic = NodePanic {
+                    node: NodeId {
+                        system: self.id,
+                        index: node,
+                        generation: slot.generation,
+                    },
+                    name: short_type_name(behaviour.name()),
+                    message: panic_message(payload),
+                };
+                log::error!("{}", panic);
+                slot.quarantined = true;
+                self.quarantine.push(panic);
+                Ok(false)
+            });
+
+            if let Err(error) = result {
+                let error = NodeError {
+                    node: NodeId {
+                        system: self.id,
+                        index: node,
+                        generation: slot.generation,
+                    },
+                    name: short_type_name(behaviour.name()),
+                    message: error.to_string(),
+                };
+                match &self.error_policy {
+                    ErrorPolicy::Abort => {
+                        self.worklist.clear();
+                        return Err(Error::Behaviour(error));
+                    }
+                    ErrorPolicy::Skip => log::warn!("{}", error),
+                    ErrorPolicy::Route(pin_in) => {
+                        let target = self.input_set_owners.get(&pin_in.handle().set_id()).copied();
+                        // a node that was already processed in this run receives the error in the next run only
+                        match target {
+                            Some(target) if self.topology.position(target) <= self.topology.position(node) => {
+                                self.routed_errors.push(error);
+                            }
+                            _ => {
+                                if !pin_in.push(&error) {
+                                    log::warn!("{} (the input of the error route was dropped)", error);
+                                }
+                                if let Some(target) = target {
+                                    if self.is_node_dirty(target) {
+                                        self.worklist.push(&self.topology, target);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            for &successor in self.topology.successors(node) {
+                if self.is_node_dirty(successor) {
+                    self.worklist.push(&self.topology, successor);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn is_node_dirty(&self, index: usize) -> bool {
+        let slot = &self.nodes[index];
+        !slot.quarantined && slot.node.as_ref().map(|node| node.is_dirty()).unwrap_or(false)
+    }
+}
+
+/// Get the message of a panic if the payload is a string.
+fn panic_message(payload: Box<dyn Any + Send>) -> Option<String> {
+    match payload.downcast::<String>() {
+        Ok(message) => Some(*message),
+        Err(payload) => payload.downcast_ref::<&str>().map(|message| message.to_string()),
     }
 }
 
@@ -0,0 +1,90 @@
+use crate::{frp::NodeId, graph::DotAttribute};
+use std::borrow::Cow;
+
+/// The amount of details in the exported graph of a system.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GraphDetail {
+    /// Behaviours and the inputs and outputs of the system connected by the edges between them.
+    Nodes,
+    /// The pins of the behaviours are also added and the edges connect the pins.
+    Pins,
+    /// Pins with the event types annotated on the pins and the edges.
+    Whole,
+}
+
+/// The kind of an exported graph node.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GraphNodeKind {
+    Behaviour(NodeId),
+    /// Input of the system given by the pin id.
+    SystemInput(usize),
+    /// Output of the system given by the pin id.
+    SystemOutput(usize),
+    /// Input pin of a behaviour given by the pin id.
+    InputPin(NodeId, usize),
+    /// Output pin of a behaviour given by the pin id.
+    OutputPin(NodeId, usize),
+}
+
+/// Node of the exported graph of a system.
+pub struct GraphNode {
+    pub kind: GraphNodeKind,
+    pub label: String,
+}
+
+impl DotAttribute for GraphNode {
+    fn label(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(&self.label))
+    }
+
+    fn font_size(&self) -> Option<u32> {
+        match self.kind {
+            GraphNodeKind::InputPin(..) | GraphNodeKind::OutputPin(..) => Some(8),
+            _ => None,
+        }
+    }
+}
+
+/// The kind of an exported graph edge.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GraphEdgeKind {
+    /// Edge between a behaviour and one of its own pins.
+    Pin,
+    /// Connection from an output to an input.
+    Connection,
+    /// Delayed connection from an output to an input.
+    Delayed,
+}
+
+/// Edge of the exported graph of a system.
+pub struct GraphEdge {
+    pub kind: GraphEdgeKind,
+    pub label: Option<String>,
+}
+
+impl DotAttribute for GraphEdge {
+    fn label(&self) -> Option<Cow<'_, str>> {
+        self.label.as_deref().map(Cow::Borrowed)
+    }
+
+    fn font_size(&self) -> Option<u32> {
+        Some(8)
+    }
+}
+
+/// Strip the module paths from a type name, ex. `alloc::vec::Vec<alloc::string::String>` becomes `Vec<String>`.
+pub(in crate::frp) fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (i, c) in name.char_indices() {
+        if c == ':' {
+            segment_start = i + 1;
+        } else if !(c.is_alphanumeric() || c == '_') {
+            short.push_str(&name[segment_start..i]);
+            short.push(c);
+            segment_start = i + c.len_utf8();
+        }
+    }
+    short.push_str(&name[segment_start..]);
+    short
+}
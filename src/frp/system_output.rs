@@ -0,0 +1,183 @@
+use crate::frp::{downcast, Error, Event, InPin, InputSet, InputSetId, SetId, SetOwner, TypedInHandle};
+use downcast_rs::{impl_downcast, Downcast};
+use std::{
+    any::{self, Any, TypeId},
+    collections::VecDeque,
+    mem,
+};
+
+/// The number of the events kept for draining by default.
+pub(in crate::frp) const DEFAULT_OUTPUT_CAPACITY: usize = 1024;
+
+/// Callback of a system output called with the received events.
+pub(in crate::frp) type OutputCallback<T> = Box<dyn FnMut(&T)>;
+
+/// Events received by an output of the system.
+pub(in crate::frp) struct SystemOutput<T: Event> {
+    last: Option<T>,
+    /// Events kept until they are drained, the oldest events are dropped above the capacity
+    queue: VecDeque<T>,
+    capacity: usize,
+    callbacks: Vec<OutputCallback<T>>,
+    /// Events not passed to the callbacks yet
+    pending: Vec<T>,
+}
+
+impl<T: Event> Default for SystemOutput<T> {
+    fn default() -> Self {
+        Self {
+            last: None,
+            queue: VecDeque::new(),
+            capacity: DEFAULT_OUTPUT_CAPACITY,
+            callbacks: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<T: Event> SystemOutput<T> {
+    pub fn last(&self) -> Option<&T> {
+        self.last.as_ref()
+    }
+
+    pub fn drain(&mut self) -> Vec<T> {
+        self.queue.drain(..).collect()
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        let excess = self.queue.len().saturating_sub(capacity);
+        self.queue.drain(..excess);
+    }
+
+    pub fn add_callback(&mut self, callback: OutputCallback<T>) {
+        self.callbacks.push(callback);
+    }
+}
+
+/// Type erased `SystemOutput`.
+pub(in crate::frp) trait GeneralSystemOutput: Downcast {
+    /// Store a type erased event received by the given pin. If the type cannot be downcasted to the type of the
+    /// output, an error is returned.
+    fn push_any(&mut self, set: SetId, pin_id: usize, event: &dyn Any) -> Result<(), Error>;
+
+    /// Get the type of the received event
+    fn event_type_id(&self) -> TypeId;
+
+    /// Get the name of the type of the received event
+    fn event_type_name(&self) -> &'static str;
+
+    /// Pass the events received since the last notification to the callbacks.
+    fn notify(&mut self);
+}
+impl_downcast!(GeneralSystemOutput);
+
+impl<T: Event> GeneralSystemOutput for SystemOutput<T> {
+    fn push_any(&mut self, set: SetId, pin_id: usize, event: &dyn Any) -> Result<(), Error> {
+        let event = downcast::<T>(event, set, pin_id)?;
+        self.last = Some(event.clone());
+        if !self.callbacks.is_empty() {
+            self.pending.push(event.clone());
+        }
+        if self.capacity > 0 {
+            if self.queue.len() == self.capacity {
+                self.queue.pop_front();
+            }
+            self.queue.push_back(event.clone());
+        }
+        Ok(())
+    }
+
+    fn event_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn event_type_name(&self) -> &'static str {
+        any::type_name::<T>()
+    }
+
+    fn notify(&mut self) {
+        for event in mem::take(&mut self.pending) {
+            for callback in &mut self.callbacks {
+                callback(&event);
+            }
+        }
+    }
+}
+
+/// The outputs of the system, the clients of the graph can observe the events received by these inputs.
+pub(in crate::frp) struct SystemOutputSet {
+    id: InputSetId,
+    owner: SetOwner,
+    outputs: Vec<Box<dyn GeneralSystemOutput>>,
+}
+
+impl Default for SystemOutputSet {
+    fn default() -> Self {
+        Self {
+            id: InputSetId::new(),
+            owner: SetOwner::default(),
+            outputs: Vec::new(),
+        }
+    }
+}
+
+impl SystemOutputSet {
+    pub fn add<T: Event>(&mut self) -> usize {
+        let id = self.outputs.len();
+        self.outputs.push(Box::new(SystemOutput::<T>::default()));
+        id
+    }
+
+    pub fn get<T: Event>(&mut self, handle: &TypedInHandle<T>) -> Option<&mut SystemOutput<T>> {
+        if handle.handle().set_id() == self.id {
+            self.outputs
+                .get_mut(handle.handle().pin_id())
+                .and_then(|o| (**o).downcast_mut::<SystemOutput<T>>())
+        } else {
+            None
+        }
+    }
+
+    pub fn notify(&mut self) {
+        for output in &mut self.outputs {
+            output.notify();
+        }
+    }
+}
+
+impl InputSet for SystemOutputSet {
+    fn id(&self) -> InputSetId {
+        self.id
+    }
+
+    fn owner(&self) -> &SetOwner {
+        &self.owner
+    }
+
+    fn pins(&self) -> Vec<InPin> {
+        self.outputs
+            .iter()
+            .enumerate()
+            .map(|(pin_id, output)| InPin {
+                pin_id,
+                event_type_id: output.event_type_id(),
+                event_type_name: output.event_type_name(),
+            })
+            .collect()
+    }
+
+    fn push(&mut self, id: usize, event: &dyn Any) -> Result<(), Error> {
+        let output = self.outputs.get_mut(id).ok_or(Error::PinNotFound {
+            set: self.id.into(),
+            index: id,
+        })?;
+        output.push_any(self.id.into(), id, event)
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+
+    fn reset_dirty(&mut self) {}
+}
@@ -0,0 +1,109 @@
+use crate::frp::{Event, InHandle, InputSetId, OutputSetId};
+use std::{
+    any::{self, Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Debug,
+    rc::Rc,
+};
+
+/// Observe the events flowing through the connections of a system.
+pub trait Tracer {
+    /// Called for each delivery of an event from an output to a connected input.
+    fn trace(&mut self, delivery: &Delivery<'_>);
+}
+
+impl<F> Tracer for F
+where
+    F: FnMut(&Delivery<'_>),
+{
+    fn trace(&mut self, delivery: &Delivery<'_>) {
+        self(delivery)
+    }
+}
+
+type DebugFormatter = fn(&dyn Any) -> String;
+
+/// Delivery of an event from an output to an input.
+pub struct Delivery<'a> {
+    pub source_set: OutputSetId,
+    pub source_pin: usize,
+    pub target_set: InputSetId,
+    pub target_pin: usize,
+    pub event_type_name: &'static str,
+    pub event: &'a dyn Any,
+    formatter: Option<DebugFormatter>,
+}
+
+impl<'a> Delivery<'a> {
+    /// Return the Debug rendering of the event, if the event type was registered by `System::trace_debug`.
+    pub fn debug(&self) -> Option<String> {
+        self.formatter.map(|format| format(self.event))
+    }
+}
+
+fn format_debug<T: Debug + 'static>(event: &dyn Any) -> String {
+    // NO-PANIC: the formatters are selected by the type id of the event
+    format!("{:?}", event.downcast_ref::<T>().unwrap())
+}
+
+/// Shared state of the tracing of a system that is installed into the outputs.
+#[derive(Clone)]
+pub struct TraceSink {
+    tracer: Rc<RefCell<Option<Box<dyn Tracer>>>>,
+    formatters: Rc<RefCell<HashMap<TypeId, DebugFormatter>>>,
+}
+
+impl Default for TraceSink {
+    fn default() -> Self {
+        Self {
+            tracer: Rc::new(RefCell::new(None)),
+            formatters: Rc::new(RefCell::new(HashMap::new())),
+        }
+    }
+}
+
+impl TraceSink {
+    pub(in crate::frp) fn set_tracer(&self, tracer: Option<Box<dyn Tracer>>) {
+        *self.tracer.borrow_mut() = tracer;
+    }
+
+    pub(in crate::frp) fn add_debug<T: Event + Debug>(&self) {
+        self.formatters
+            .borrow_mut()
+            .insert(TypeId::of::<T>(), format_debug::<T>);
+    }
+}
+
+/// Tracing of an output pin.
+#[derive(Clone)]
+pub(in crate::frp) struct PinTracer {
+    sink: TraceSink,
+    set_id: OutputSetId,
+    pin_id: usize,
+}
+
+impl PinTracer {
+    pub fn new(sink: &TraceSink, set_id: OutputSetId, pin_id: usize) -> Self {
+        Self {
+            sink: sink.clone(),
+            set_id,
+            pin_id,
+        }
+    }
+
+    pub fn trace<T: Event>(&self, target: &InHandle, event: &T) {
+        if let Some(tracer) = &mut *self.sink.tracer.borrow_mut() {
+            let delivery = Delivery {
+                source_set: self.set_id,
+                source_pin: self.pin_id,
+                target_set: target.set_id(),
+                target_pin: target.pin_id(),
+                event_type_name: any::type_name::<T>(),
+                event,
+                formatter: self.sink.formatters.borrow().get(&TypeId::of::<T>()).copied(),
+            };
+            tracer.trace(&delivery);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use crate::frp::{Event, OutHandle, TypedOutHandle};
+use std::any::Any;
+
+/// Events for multiple inputs of a system that are propagated together, see `System::commit`.
+/// Each node is executed at most once and observes all the changes of the transaction.
+#[derive(Default)]
+pub struct Transaction {
+    pub(in crate::frp) events: Vec<(OutHandle, Box<dyn Any>)>,
+}
+
+impl Transaction {
+    /// Queue an event for an input of the system.
+    pub fn send<T: Event>(&mut self, input: &TypedOutHandle<T>, event: T) -> &mut Self {
+        self.events.push((input.handle().clone(), Box::new(event)));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
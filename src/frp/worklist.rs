@@ -0,0 +1,38 @@
+use crate::graph::TopologyOrder;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// Nodes waiting for execution ordered by their position in the topology.
+#[derive(Default)]
+pub(in crate::frp) struct Worklist {
+    queue: BinaryHeap<Reverse<(usize, usize)>>,
+    queued: Vec<bool>,
+}
+
+impl Worklist {
+    /// Add a node to the worklist if it is not queued yet.
+    /// #Panic
+    /// This function may panic if the node is not part of the topology.
+    pub fn push(&mut self, topology: &TopologyOrder, node: usize) {
+        if node >= self.queued.len() {
+            self.queued.resize(node + 1, false);
+        }
+        if !self.queued[node] {
+            self.queued[node] = true;
+            let position = topology.position(node).expect("Node is not part of the topology");
+            self.queue.push(Reverse((position, node)));
+        }
+    }
+
+    /// Remove the node with the lowest position.
+    pub fn pop(&mut self) -> Option<usize> {
+        let Reverse((_, node)) = self.queue.pop()?;
+        self.queued[node] = false;
+        Some(node)
+    }
+
+    /// Remove all the nodes.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.queued.iter_mut().for_each(|queued| *queued = false);
+    }
+}
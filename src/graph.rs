@@ -1,4 +1,4 @@
-use std::{borrow::Cow, fmt};
+use std::{borrow::Cow, fmt, mem};
 
 pub struct Node<N> {
     pub data: N,
@@ -19,17 +19,19 @@ pub struct Graph<N, E> {
 impl<N, E> Graph<N, E> {
     /// Construct the topology order of the nodes. If graph contains a cycle, None is returned.
     pub fn get_topology_order(&self) -> Option<Vec<usize>> {
-        let mut order = Vec::new();
+        let mut order = Vec::with_capacity(self.nodes.len());
 
         let mut active = Vec::new();
-        let mut degrees = Vec::with_capacity(self.nodes.len());
-        let mut edges = self.edges.iter().map(|edge| (edge.from, edge.to)).collect::<Vec<_>>();
+        let mut degrees = vec![0; self.nodes.len()];
+        let mut successors = vec![Vec::new(); self.nodes.len()];
 
         // find degree for each node and colleect roots
-        for n in 0..self.nodes.len() {
-            let degree = edges.iter().filter(|(_, t)| n == *t).count();
-            degrees.push(degree);
-            if degree == 0 {
+        for edge in &self.edges {
+            degrees[edge.to] += 1;
+            successors[edge.from].push(edge.to);
+        }
+        for (n, degree) in degrees.iter().enumerate() {
+            if *degree == 0 {
                 active.push(n);
             }
         }
@@ -37,20 +39,15 @@ impl<N, E> Graph<N, E> {
         while let Some(n) = active.pop() {
             order.push(n);
 
-            edges.retain(|(f, t)| {
-                if n == *f {
-                    degrees[*t] -= 1;
-                    if degrees[*t] == 0 {
-                        active.push(*t);
-                    }
-                    false
-                } else {
-                    true
+            for t in &successors[n] {
+                degrees[*t] -= 1;
+                if degrees[*t] == 0 {
+                    active.push(*t);
                 }
-            });
+            }
         }
 
-        if edges.is_empty() {
+        if order.len() == self.nodes.len() {
             Some(order)
         } else {
             None
@@ -58,6 +55,201 @@ impl<N, E> Graph<N, E> {
     }
 }
 
+/// Topological order of a graph maintained incrementally while nodes and edges are added or removed.
+/// Instead of sorting the whole graph, only the region affected by a new edge is reordered, see
+/// Pearce and Kelly: A Dynamic Topological Sort Algorithm for Directed Acyclic Graphs,
+/// <https://www.doc.ic.ac.uk/~phjk/Publications/DynamicTopoSortAlg-JEA-07.pdf>
+#[derive(Default)]
+pub struct TopologyOrder {
+    /// Outgoing edges of the nodes. Parallel edges are stored multiple times.
+    successors: Vec<Vec<usize>>,
+    /// Incoming edges of the nodes. Parallel edges are stored multiple times.
+    predecessors: Vec<Vec<usize>>,
+    /// Position of the nodes in the order, None if node is not part of the graph
+    positions: Vec<Option<usize>>,
+    /// The nodes in order, None for the position of a removed node
+    order: Vec<Option<usize>>,
+    /// Number of the positions of removed nodes in the order
+    holes: usize,
+    /// Marker for the searches
+    visited: Vec<bool>,
+}
+
+impl TopologyOrder {
+    /// Add a node to the end of the order.
+    /// #Panic
+    /// This function may panic if the node is already part of the graph.
+    pub fn add_node(&mut self, node: usize) {
+        if node >= self.positions.len() {
+            self.positions.resize(node + 1, None);
+            self.successors.resize_with(node + 1, Vec::new);
+            self.predecessors.resize_with(node + 1, Vec::new);
+            self.visited.resize(node + 1, false);
+        }
+        assert!(self.positions[node].is_none(), "Node is already part of the graph");
+        self.positions[node] = Some(self.order.len());
+        self.order.push(Some(node));
+    }
+
+    /// Remove a node and all of its edges. The order of the other nodes is not altered.
+    pub fn remove_node(&mut self, node: usize) {
+        if let Some(position) = self.positions.get_mut(node).and_then(Option::take) {
+            for successor in mem::take(&mut self.successors[node]) {
+                Self::remove_one(&mut self.predecessors[successor], node);
+            }
+            for predecessor in mem::take(&mut self.predecessors[node]) {
+                Self::remove_one(&mut self.successors[predecessor], node);
+            }
+
+            self.order[position] = None;
+            self.holes += 1;
+            if self.holes * 2 > self.order.len() {
+                self.compact();
+            }
+        }
+    }
+
+    pub fn contains(&self, node: usize) -> bool {
+        self.position(node).is_some()
+    }
+
+    /// Return the position of the node in the order. The positions are increasing along the order, but
+    /// they are not continuous.
+    pub fn position(&self, node: usize) -> Option<usize> {
+        self.positions.get(node).copied().flatten()
+    }
+
+    pub fn successors(&self, node: usize) -> &[usize] {
+        self.successors.get(node).map(|s| &s[..]).unwrap_or(&[])
+    }
+
+    pub fn predecessors(&self, node: usize) -> &[usize] {
+        self.predecessors.get(node).map(|p| &p[..]).unwrap_or(&[])
+    }
+
+    /// Iterate the nodes in topological order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.order.iter().flatten().copied()
+    }
+
+    /// Add an edge and update the order. If the edge would create a cycle, false is returned and the
+    /// graph is not altered.
+    /// #Panic
+    /// This function may panic if any of the nodes is not part of the graph.
+    pub fn add_edge(&mut self, from: usize, to: usize) -> bool {
+        let upper = self.positions[from].expect("Node is not part of the graph");
+        let lower = self.positions[to].expect("Node is not part of the graph");
+        if from == to {
+            return false;
+        }
+
+        if lower < upper {
+            // the nodes affected by the new edge
+            let mut forward = Vec::new();
+            if !self.search_forward(to, upper, &mut forward) {
+                for node in forward {
+                    self.visited[node] = false;
+                }
+                return false;
+            }
+            let mut backward = Vec::new();
+            self.search_backward(from, lower, &mut backward);
+            self.reorder(backward, forward);
+        }
+
+        self.successors[from].push(to);
+        self.predecessors[to].push(from);
+        true
+    }
+
+    /// Remove an edge. The order is kept as it remains valid. If there is no such edge, false is returned.
+    pub fn remove_edge(&mut self, from: usize, to: usize) -> bool {
+        match (self.successors.get_mut(from), self.predecessors.get_mut(to)) {
+            (Some(successors), Some(predecessors)) if successors.contains(&to) => {
+                Self::remove_one(successors, to);
+                Self::remove_one(predecessors, from);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn remove_one(nodes: &mut Vec<usize>, node: usize) {
+        if let Some(i) = nodes.iter().position(|n| *n == node) {
+            nodes.swap_remove(i);
+        }
+    }
+
+    /// Collect the nodes reachable from start and positioned before upper. If the node at upper is
+    /// reachable, a cycle is found and false is returned.
+    fn search_forward(&mut self, start: usize, upper: usize, nodes: &mut Vec<usize>) -> bool {
+        let mut stack = vec![start];
+        self.visited[start] = true;
+        nodes.push(start);
+        while let Some(node) = stack.pop() {
+            for &successor in &self.successors[node] {
+                // NO-PANIC: all the edges connect nodes of the graph
+                let position = self.positions[successor].unwrap();
+                if position == upper {
+                    return false;
+                }
+                if position < upper && !self.visited[successor] {
+                    self.visited[successor] = true;
+                    nodes.push(successor);
+                    stack.push(successor);
+                }
+            }
+        }
+        true
+    }
+
+    /// Collect the nodes start is reachable from and positioned after lower.
+    fn search_backward(&mut self, start: usize, lower: usize, nodes: &mut Vec<usize>) {
+        let mut stack = vec![start];
+        self.visited[start] = true;
+        nodes.push(start);
+        while let Some(node) = stack.pop() {
+            for &predecessor in &self.predecessors[node] {
+                // NO-PANIC: all the edges connect nodes of the graph
+                let position = self.positions[predecessor].unwrap();
+                if position > lower && !self.visited[predecessor] {
+                    self.visited[predecessor] = true;
+                    nodes.push(predecessor);
+                    stack.push(predecessor);
+                }
+            }
+        }
+    }
+
+    /// Move the backward nodes in front of the forward nodes reusing the positions they occupied.
+    fn reorder(&mut self, mut backward: Vec<usize>, mut forward: Vec<usize>) {
+        // NO-PANIC: all the visited nodes are part of the graph
+        backward.sort_unstable_by_key(|node| self.positions[*node].unwrap());
+        forward.sort_unstable_by_key(|node| self.positions[*node].unwrap());
+
+        let mut positions = backward
+            .iter()
+            .chain(forward.iter())
+            .map(|node| self.positions[*node].unwrap())
+            .collect::<Vec<_>>();
+        positions.sort_unstable();
+
+        for (node, position) in backward.into_iter().chain(forward).zip(positions) {
+            self.visited[node] = false;
+            self.positions[node] = Some(position);
+            self.order[position] = Some(node);
+        }
+    }
+
+    fn compact(&mut self) {
+        self.order.retain(Option::is_some);
+        for (position, node) in self.order.iter().flatten().enumerate() {
+            self.positions[*node] = Some(position);
+        }
+        self.holes = 0;
+    }
+}
+
 /// Customize dot vizualization.
 pub trait DotAttribute {
     fn label(&self) -> Option<Cow<'_, str>> {
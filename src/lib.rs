@@ -1,4 +1,2 @@
-#![feature(entry_insert)]
-
 pub mod frp;
 pub mod graph;
@@ -1,17 +1,33 @@
-use frp::{
-    frp::{
-        behaviours::Inspector, inputs::StoreLast, Behaviour, BehaviourNode, Error, FixedInputSet, FixedOutSet,
-        IntoBehaviourNode, Out, System, TypedInHandle, TypedOutHandle,
-    }
+use frp::frp::{
+    behaviours::Inspector,
+    inputs::{Bounded, OverflowPolicy, Sampled, StoreIfChanged, StoreIfChangedApprox, StoreLast, Unbounded},
+    Behaviour, BehaviourError, BehaviourNode, BehaviourRegistry, Dangling, Delivery, DynamicInputSet, Error,
+    ErrorPolicy, FiringPolicy, FixedInputSet, FixedOutSet, GraphDetail, GraphEdgeKind, GraphNodeKind, In, InputSet,
+    IntoBehaviourNode, NodeError, Out, OutputSet, PinReference, SetId, System, SystemDescription, Transaction,
+    TypedInHandle, TypedOutHandle,
 };
-use std::{cell::RefCell, rc::Rc};
+use serde::Deserialize;
+use std::{any::TypeId, cell::RefCell, num::NonZeroUsize, rc::Rc};
+
+/// Implement `IntoBehaviourNode` for behaviours with default constructed input and output sets.
+macro_rules! into_behaviour_node {
+    ($behaviour:ty) => {
+        impl IntoBehaviourNode for $behaviour {
+            type Behaviour = Self;
+
+            fn into_behaviour_node(self) -> Result<BehaviourNode<Self::Behaviour>, Error> {
+                BehaviourNode::new(FixedInputSet::default(), FixedOutSet::default(), self)
+            }
+        }
+    };
+}
 
 pub struct StringDublicatorPinLayout {
     pub input: TypedInHandle<String>,
     pub output: TypedOutHandle<String>,
 }
 
-#[derive(Default)]
+#[derive(Default, Deserialize)]
 pub struct StringDublicator;
 
 impl Behaviour for StringDublicator {
@@ -37,27 +53,1377 @@ impl Behaviour for StringDublicator {
     }
 }
 
-impl IntoBehaviourNode for StringDublicator {
-    type Behaviour = Self;
+into_behaviour_node!(StringDublicator);
+
+pub struct RecorderPinLayout {
+    pub input: TypedInHandle<String>,
+}
+
+/// Store all the received events for the assertions.
+#[derive(Default)]
+pub struct Recorder(Rc<RefCell<Vec<String>>>);
+
+impl Behaviour for Recorder {
+    type InputSet = FixedInputSet<StoreLast<String>>;
+    type OutputSet = FixedOutSet<()>;
+    type PinLayout = RecorderPinLayout;
+
+    fn behave(&mut self, input_set: &mut Self::InputSet, _output_set: &mut Self::OutputSet) {
+        let input = &mut **input_set;
+        self.0.borrow_mut().extend(input.take());
+    }
+
+    fn get_pins(
+        &self,
+        input_set: &Rc<RefCell<Self::InputSet>>,
+        _output_set: &Rc<RefCell<Self::OutputSet>>,
+    ) -> Self::PinLayout {
+        RecorderPinLayout {
+            input: TypedInHandle::new(input_set, 0),
+        }
+    }
+}
+
+into_behaviour_node!(Recorder);
+
+pub struct DistinctPinLayout {
+    pub input: TypedInHandle<String>,
+    pub output: TypedOutHandle<String>,
+}
+
+/// Forward the changes of the input only.
+#[derive(Default)]
+pub struct Distinct;
+
+impl Behaviour for Distinct {
+    type InputSet = FixedInputSet<StoreIfChanged<String>>;
+    type OutputSet = FixedOutSet<Out<String>>;
+    type PinLayout = DistinctPinLayout;
+
+    fn behave(&mut self, input_set: &mut Self::InputSet, output_set: &mut Self::OutputSet) {
+        let input = &**input_set.get();
+        let output = &mut **output_set;
+        output.send(&input.to_string());
+    }
+
+    fn get_pins(
+        &self,
+        input_set: &Rc<RefCell<Self::InputSet>>,
+        output_set: &Rc<RefCell<Self::OutputSet>>,
+    ) -> Self::PinLayout {
+        DistinctPinLayout {
+            input: TypedInHandle::new(input_set, 0),
+            output: TypedOutHandle::new(output_set, 0),
+        }
+    }
+}
+
+into_behaviour_node!(Distinct);
+
+pub struct NonEmptyPinLayout {
+    pub input: TypedInHandle<String>,
+    pub output: TypedOutHandle<String>,
+}
+
+/// Forward the input and fail on an empty string.
+#[derive(Default)]
+pub struct NonEmpty;
+
+impl Behaviour for NonEmpty {
+    type InputSet = FixedInputSet<StoreLast<String>>;
+    type OutputSet = FixedOutSet<Out<String>>;
+    type PinLayout = NonEmptyPinLayout;
+
+    fn try_behave(
+        &mut self,
+        input_set: &mut Self::InputSet,
+        output_set: &mut Self::OutputSet,
+    ) -> Result<(), BehaviourError> {
+        let input = &**input_set.get();
+        if input.is_empty() {
+            return Err("empty input".into());
+        }
+        output_set.send(&input.to_string());
+        Ok(())
+    }
+
+    fn get_pins(
+        &self,
+        input_set: &Rc<RefCell<Self::InputSet>>,
+        output_set: &Rc<RefCell<Self::OutputSet>>,
+    ) -> Self::PinLayout {
+        NonEmptyPinLayout {
+            input: TypedInHandle::new(input_set, 0),
+            output: TypedOutHandle::new(output_set, 0),
+        }
+    }
+}
+
+into_behaviour_node!(NonEmpty);
+
+pub struct PanickingPinLayout {
+    pub input: TypedInHandle<String>,
+    pub output: TypedOutHandle<String>,
+}
+
+/// Panic on an empty string.
+#[derive(Default)]
+pub struct Panicking;
+
+impl Behaviour for Panicking {
+    type InputSet = FixedInputSet<StoreLast<String>>;
+    type OutputSet = FixedOutSet<Out<String>>;
+    type PinLayout = PanickingPinLayout;
+
+    fn behave(&mut self, input_set: &mut Self::InputSet, output_set: &mut Self::OutputSet) {
+        let input = input_set.get().to_string();
+        output_set.send(&input);
+        assert!(!input.is_empty(), "empty input");
+    }
+
+    fn get_pins(
+        &self,
+        input_set: &Rc<RefCell<Self::InputSet>>,
+        output_set: &Rc<RefCell<Self::OutputSet>>,
+    ) -> Self::PinLayout {
+        PanickingPinLayout {
+            input: TypedInHandle::new(input_set, 0),
+            output: TypedOutHandle::new(output_set, 0),
+        }
+    }
+}
+
+into_behaviour_node!(Panicking);
+
+pub struct RetryPinLayout {
+    pub input: TypedInHandle<String>,
+    pub errors: TypedInHandle<NodeError>,
+    pub output: TypedOutHandle<String>,
+}
+
+/// Forward the input and record the calls, a received error replaces the input by a fallback.
+pub struct Retry(Rc<RefCell<Vec<String>>>);
+
+impl Behaviour for Retry {
+    type InputSet = FixedInputSet<(StoreLast<String>, StoreLast<NodeError>)>;
+    type OutputSet = FixedOutSet<Out<String>>;
+    type PinLayout = RetryPinLayout;
+
+    fn behave(&mut self, input_set: &mut Self::InputSet, output_set: &mut Self::OutputSet) {
+        let event = if input_set.changed(1) {
+            "fallback".to_string()
+        } else {
+            input_set.0.get().clone()
+        };
+        self.0.borrow_mut().push(event.clone());
+        output_set.send(&event);
+    }
 
-    fn into_behaviour_node(self) -> Result<BehaviourNode<Self::Behaviour>, Error> {
-        let input_set = FixedInputSet::default();
-        let output_set = FixedOutSet::default();
-        Ok(BehaviourNode::new(input_set, output_set, self))
+    fn get_pins(
+        &self,
+        input_set: &Rc<RefCell<Self::InputSet>>,
+        output_set: &Rc<RefCell<Self::OutputSet>>,
+    ) -> Self::PinLayout {
+        RetryPinLayout {
+            input: TypedInHandle::new(input_set, 0),
+            errors: TypedInHandle::new(input_set, 1),
+            output: TypedOutHandle::new(output_set, 0),
+        }
     }
 }
 
+into_behaviour_node!(Retry);
+
 #[test]
 fn simple() {
     let mut system = System::default();
     let input = system.create_input::<String>();
 
-    let string_dup = system.add_behaviour(StringDublicator::default()).unwrap();
+    let string_dup = system.add_behaviour(StringDublicator).unwrap();
     let inspect = system.add_behaviour(Inspector::<String>::default()).unwrap();
 
     system.connect(&input, &string_dup.input).unwrap();
     system.connect(&string_dup.output, &inspect.input).unwrap();
 
-    //log::trace!("{}", system.get_dot_graph(GraphDetail::Whole));
+    log::trace!("{}", system.get_dot_graph(GraphDetail::Whole));
     system.run_on(input, &"Hello World".to_string()).unwrap();
 }
+
+#[test]
+fn topology_order() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+
+    // add the nodes in the reverse order of the event flow
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let recorder = system.add_behaviour(Recorder(events.clone())).unwrap();
+    let dup2 = system.add_behaviour(StringDublicator).unwrap();
+    let dup1 = system.add_behaviour(StringDublicator).unwrap();
+
+    system.connect(&dup2.output, &recorder.input).unwrap();
+    system.connect(&dup1.output, &dup2.input).unwrap();
+    system.connect(&input, &dup1.input).unwrap();
+
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+    assert_eq!(*events.borrow(), vec!["aaaa".to_string()]);
+
+    system.run_on(input, &"b".to_string()).unwrap();
+    assert_eq!(*events.borrow(), vec!["aaaa".to_string(), "bbbb".to_string()]);
+}
+
+#[test]
+fn cycle() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let recorder = system.add_behaviour(Recorder(events.clone())).unwrap();
+    let dup1 = system.add_behaviour(StringDublicator).unwrap();
+    let dup2 = system.add_behaviour(StringDublicator).unwrap();
+
+    system.connect(&input, &dup1.input).unwrap();
+    system.connect(&dup1.output, &dup2.input).unwrap();
+    system.connect(&dup2.output, &recorder.input).unwrap();
+
+    assert!(matches!(system.connect(&dup2.output, &dup1.input), Err(Error::Cycle)));
+    assert!(matches!(system.connect(&dup1.output, &dup1.input), Err(Error::Cycle)));
+
+    // the rejected connections shall leave the system unchanged
+    system.run_on(input, &"a".to_string()).unwrap();
+    assert_eq!(*events.borrow(), vec!["aaaa".to_string()]);
+}
+
+pub struct AccumulatorPinLayout {
+    pub input: TypedInHandle<String>,
+    pub previous: TypedInHandle<String>,
+    pub output: TypedOutHandle<String>,
+}
+
+/// Concatenate the input to the previous output.
+pub struct Accumulator;
+
+impl Behaviour for Accumulator {
+    type InputSet = FixedInputSet<(StoreLast<String>, StoreLast<String>)>;
+    type OutputSet = FixedOutSet<Out<String>>;
+    type PinLayout = AccumulatorPinLayout;
+
+    fn behave(&mut self, input_set: &mut Self::InputSet, output_set: &mut Self::OutputSet) {
+        let (input, previous) = &mut **input_set;
+        if let Some(input) = input.take() {
+            let previous = previous.try_get().cloned().unwrap_or_default();
+            let output = &mut **output_set;
+            output.send(&format!("{}{}", previous, input));
+        }
+    }
+
+    fn get_pins(
+        &self,
+        input_set: &Rc<RefCell<Self::InputSet>>,
+        output_set: &Rc<RefCell<Self::OutputSet>>,
+    ) -> Self::PinLayout {
+        AccumulatorPinLayout {
+            input: TypedInHandle::new(input_set, 0),
+            previous: TypedInHandle::new(input_set, 1),
+            output: TypedOutHandle::new(output_set, 0),
+        }
+    }
+}
+
+into_behaviour_node!(Accumulator);
+
+pub struct ChangeRecorderPinLayout {
+    pub first: TypedInHandle<String>,
+    pub second: TypedInHandle<String>,
+    pub output: TypedOutHandle<String>,
+}
+
+/// Record the changed inputs of each call.
+pub struct ChangeRecorder(Rc<RefCell<Vec<u64>>>);
+
+impl Behaviour for ChangeRecorder {
+    type InputSet = FixedInputSet<(StoreLast<String>, StoreLast<String>)>;
+    type OutputSet = FixedOutSet<Out<String>>;
+    type PinLayout = ChangeRecorderPinLayout;
+
+    fn behave(&mut self, input_set: &mut Self::InputSet, _output_set: &mut Self::OutputSet) {
+        assert_eq!(input_set.changed(0), input_set.changed_mask() & 1 != 0);
+        self.0.borrow_mut().push(input_set.changed_mask());
+    }
+
+    fn get_pins(
+        &self,
+        input_set: &Rc<RefCell<Self::InputSet>>,
+        output_set: &Rc<RefCell<Self::OutputSet>>,
+    ) -> Self::PinLayout {
+        ChangeRecorderPinLayout {
+            first: TypedInHandle::new(input_set, 0),
+            second: TypedInHandle::new(input_set, 1),
+            output: TypedOutHandle::new(output_set, 0),
+        }
+    }
+}
+
+into_behaviour_node!(ChangeRecorder);
+
+pub struct JoinPinLayout {
+    pub first: TypedInHandle<String>,
+    pub second: TypedInHandle<String>,
+    pub output: TypedOutHandle<String>,
+}
+
+/// Concatenate the last values of the inputs when the firing policy allows.
+pub struct Join(FiringPolicy);
+
+impl Behaviour for Join {
+    type InputSet = FixedInputSet<(StoreLast<String>, StoreLast<String>)>;
+    type OutputSet = FixedOutSet<Out<String>>;
+    type PinLayout = JoinPinLayout;
+
+    fn firing_policy(&self) -> FiringPolicy {
+        self.0.clone()
+    }
+
+    fn behave(&mut self, input_set: &mut Self::InputSet, output_set: &mut Self::OutputSet) {
+        let (first, second) = &**input_set;
+        let first = first.try_get().cloned().unwrap_or_default();
+        let second = second.try_get().cloned().unwrap_or_default();
+        let output = &mut **output_set;
+        output.send(&format!("{}{}", first, second));
+    }
+
+    fn get_pins(
+        &self,
+        input_set: &Rc<RefCell<Self::InputSet>>,
+        output_set: &Rc<RefCell<Self::OutputSet>>,
+    ) -> Self::PinLayout {
+        JoinPinLayout {
+            first: TypedInHandle::new(input_set, 0),
+            second: TypedInHandle::new(input_set, 1),
+            output: TypedOutHandle::new(output_set, 0),
+        }
+    }
+}
+
+into_behaviour_node!(Join);
+
+pub struct SamplePinLayout {
+    pub input: TypedInHandle<String>,
+    pub sampled: TypedInHandle<String>,
+    pub output: TypedOutHandle<String>,
+}
+
+/// Concatenate the input to the sampled value of the second input.
+pub struct Sample;
+
+impl Behaviour for Sample {
+    type InputSet = FixedInputSet<(StoreLast<String>, Sampled<StoreLast<String>>)>;
+    type OutputSet = FixedOutSet<Out<String>>;
+    type PinLayout = SamplePinLayout;
+
+    fn behave(&mut self, input_set: &mut Self::InputSet, output_set: &mut Self::OutputSet) {
+        let (input, sampled) = &mut **input_set;
+        if let Some(input) = input.take() {
+            let sampled = sampled.try_get().cloned().unwrap_or_default();
+            let output = &mut **output_set;
+            output.send(&format!("{}{}", input, sampled));
+        }
+    }
+
+    fn get_pins(
+        &self,
+        input_set: &Rc<RefCell<Self::InputSet>>,
+        output_set: &Rc<RefCell<Self::OutputSet>>,
+    ) -> Self::PinLayout {
+        SamplePinLayout {
+            input: TypedInHandle::new(input_set, 0),
+            sampled: TypedInHandle::new(input_set, 1),
+            output: TypedOutHandle::new(output_set, 0),
+        }
+    }
+}
+
+into_behaviour_node!(Sample);
+
+#[test]
+fn delayed_feedback() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let recorder = system.add_behaviour(Recorder(events.clone())).unwrap();
+    let acc = system.add_behaviour(Accumulator).unwrap();
+
+    system.connect(&input, &acc.input).unwrap();
+    system.connect(&acc.output, &recorder.input).unwrap();
+    assert!(matches!(system.connect(&acc.output, &acc.previous), Err(Error::Cycle)));
+    system.connect_delayed(&acc.output, &acc.previous).unwrap();
+
+    for event in ["a", "b", "c"] {
+        system.run_on(input.clone(), &event.to_string()).unwrap();
+    }
+    assert_eq!(
+        *events.borrow(),
+        vec!["a".to_string(), "ab".to_string(), "abc".to_string()]
+    );
+
+    // the delayed connection is removed with its pending event
+    assert!(matches!(
+        system.disconnect(&acc.output, &acc.previous),
+        Err(Error::ConnectionNotFound)
+    ));
+    system.disconnect_delayed(&acc.output, &acc.previous).unwrap();
+    assert!(matches!(
+        system.disconnect_delayed(&acc.output, &acc.previous),
+        Err(Error::ConnectionNotFound)
+    ));
+    system.run_on(input.clone(), &"d".to_string()).unwrap();
+    system.run_on(input, &"e".to_string()).unwrap();
+    assert_eq!(events.borrow()[3..], ["abd".to_string(), "abe".to_string()]);
+
+    // the input has to be part of the system
+    let input_set = Rc::new(RefCell::new(FixedInputSet::<StoreLast<String>>::default()));
+    let unknown = TypedInHandle::<String>::new(&input_set, 0);
+    assert!(matches!(
+        system.connect_delayed(&acc.output, &unknown),
+        Err(Error::InputNotFound)
+    ));
+}
+
+#[test]
+fn affected_nodes_only() {
+    let mut system = System::default();
+    let input_a = system.create_input::<String>();
+    let input_b = system.create_input::<String>();
+
+    let events_a = Rc::new(RefCell::new(Vec::new()));
+    let events_b = Rc::new(RefCell::new(Vec::new()));
+    let recorder_a = system.add_behaviour(Recorder(events_a.clone())).unwrap();
+    let recorder_b = system.add_behaviour(Recorder(events_b.clone())).unwrap();
+    let acc = system.add_behaviour(Accumulator).unwrap();
+    let dup1 = system.add_behaviour(StringDublicator).unwrap();
+    let dup2 = system.add_behaviour(StringDublicator).unwrap();
+    let dup3 = system.add_behaviour(StringDublicator).unwrap();
+
+    // diamond on input_a, the accumulator shall fire once with both of its inputs
+    system.connect(&input_a, &dup1.input).unwrap();
+    system.connect(&input_a, &dup2.input).unwrap();
+    system.connect(&dup1.output, &acc.input).unwrap();
+    system.connect(&dup2.output, &acc.previous).unwrap();
+    system.connect(&acc.output, &recorder_a.input).unwrap();
+
+    system.connect(&input_b, &dup3.input).unwrap();
+    system.connect(&dup3.output, &recorder_b.input).unwrap();
+
+    system.run_on(input_a, &"x".to_string()).unwrap();
+    assert_eq!(*events_a.borrow(), vec!["xxxx".to_string()]);
+    assert!(events_b.borrow().is_empty());
+
+    system.run_on(input_b, &"y".to_string()).unwrap();
+    assert_eq!(*events_a.borrow(), vec!["xxxx".to_string()]);
+    assert_eq!(*events_b.borrow(), vec!["yy".to_string()]);
+}
+
+#[test]
+fn transaction() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+    let previous = system.create_input::<String>();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let recorder = system.add_behaviour(Recorder(events.clone())).unwrap();
+    let acc = system.add_behaviour(Accumulator).unwrap();
+
+    system.connect(&input, &acc.input).unwrap();
+    system.connect(&previous, &acc.previous).unwrap();
+    system.connect(&acc.output, &recorder.input).unwrap();
+
+    // separate runs propagate the intermediate state
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+    system.run_on(previous.clone(), &"b".to_string()).unwrap();
+    system.run_on(input.clone(), &"c".to_string()).unwrap();
+    assert_eq!(*events.borrow(), vec!["a".to_string(), "bc".to_string()]);
+    events.borrow_mut().clear();
+
+    // a transaction fires the node once with all the changes
+    let mut transaction = Transaction::default();
+    transaction
+        .send(&previous, "d".to_string())
+        .send(&input, "e".to_string());
+    system.commit(transaction).unwrap();
+    assert_eq!(*events.borrow(), vec!["de".to_string()]);
+
+    // a transaction with an unknown input is rejected as a whole
+    let foreign = System::default().create_input::<String>();
+    let mut transaction = Transaction::default();
+    transaction
+        .send(&input, "f".to_string())
+        .send(&foreign, "g".to_string());
+    assert!(matches!(system.commit(transaction), Err(Error::ForeignHandle)));
+    system.run_on(previous, &"h".to_string()).unwrap();
+    assert_eq!(*events.borrow(), vec!["de".to_string()]);
+}
+
+#[test]
+fn remove_behaviour() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let recorder = system.add_behaviour(Recorder(events.clone())).unwrap();
+    let dup1 = system.add_behaviour(StringDublicator).unwrap();
+    let dup2 = system.add_behaviour(StringDublicator).unwrap();
+    let acc = system.add_behaviour(Accumulator).unwrap();
+
+    system.connect(&input, &dup1.input).unwrap();
+    system.connect(&dup1.output, &dup2.input).unwrap();
+    system.connect(&dup2.output, &recorder.input).unwrap();
+    system.connect(&input, &acc.input).unwrap();
+    system.connect_delayed(&acc.output, &acc.previous).unwrap();
+    system.connect_delayed(&acc.output, &dup2.input).unwrap();
+
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+    assert_eq!(*events.borrow(), vec!["aaaa".to_string()]);
+    events.borrow_mut().clear();
+
+    system.remove_behaviour(dup2.id()).unwrap();
+    system.remove_behaviour(acc.id()).unwrap();
+    assert!(matches!(system.remove_behaviour(dup2.id()), Err(Error::NodeNotFound)));
+    assert!(matches!(
+        system.connect(&dup1.output, &dup2.input),
+        Err(Error::InputNotFound)
+    ));
+    assert!(matches!(
+        system.connect(&dup2.output, &recorder.input),
+        Err(Error::OutputNotFound)
+    ));
+
+    system.run_on(input.clone(), &"b".to_string()).unwrap();
+    assert!(events.borrow().is_empty());
+
+    // the slot of the removed node is reused, but the old id remains invalid
+    let dup3 = system.add_behaviour(StringDublicator).unwrap();
+    assert_ne!(dup3.id(), dup2.id());
+    assert!(matches!(system.remove_behaviour(dup2.id()), Err(Error::NodeNotFound)));
+    system.connect(&dup1.output, &dup3.input).unwrap();
+    system.connect(&dup3.output, &recorder.input).unwrap();
+
+    system.run_on(input, &"c".to_string()).unwrap();
+    assert_eq!(*events.borrow(), vec!["cccc".to_string()]);
+}
+
+#[test]
+fn disconnect() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let recorder = system.add_behaviour(Recorder(events.clone())).unwrap();
+    let dup1 = system.add_behaviour(StringDublicator).unwrap();
+    let dup2 = system.add_behaviour(StringDublicator).unwrap();
+
+    system.connect(&input, &dup1.input).unwrap();
+    system.connect(&dup1.output, &dup2.input).unwrap();
+    system.connect(&dup2.output, &recorder.input).unwrap();
+
+    // rewire the graph in the reverse direction
+    system.disconnect(&dup1.output, &dup2.input).unwrap();
+    assert!(matches!(
+        system.disconnect(&dup1.output, &dup2.input),
+        Err(Error::ConnectionNotFound)
+    ));
+    system.disconnect(&input, &dup1.input).unwrap();
+    system.disconnect(&dup2.output, &recorder.input).unwrap();
+    system.connect(&input, &dup2.input).unwrap();
+    system.connect(&dup2.output, &dup1.input).unwrap();
+    system.connect(&dup1.output, &recorder.input).unwrap();
+
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+    assert_eq!(*events.borrow(), vec!["aaaa".to_string()]);
+    events.borrow_mut().clear();
+
+    // remove all the connections of a pin
+    system.connect_delayed(&dup2.output, &recorder.input).unwrap();
+    system.disconnect_input(recorder.input.handle()).unwrap();
+    system.run_on(input.clone(), &"b".to_string()).unwrap();
+    system.run_on(input.clone(), &"c".to_string()).unwrap();
+    assert!(events.borrow().is_empty());
+
+    system.connect(&dup2.output, &recorder.input).unwrap();
+    system.disconnect_output(input.handle()).unwrap();
+    system.run_on(input, &"d".to_string()).unwrap();
+    assert!(events.borrow().is_empty());
+}
+
+#[test]
+fn system_output() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+    let output = system.create_output::<String>();
+
+    let dup = system.add_behaviour(StringDublicator).unwrap();
+    system.connect(&input, &dup.input).unwrap();
+    system.connect(&dup.output, &output).unwrap();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    {
+        let events = events.clone();
+        system
+            .on_output(&output, move |event: &String| events.borrow_mut().push(event.clone()))
+            .unwrap();
+    }
+
+    assert_eq!(system.last_output(&output).unwrap(), None);
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+    system.run_on(input.clone(), &"b".to_string()).unwrap();
+    assert_eq!(system.last_output(&output).unwrap(), Some("bb".to_string()));
+    assert_eq!(*events.borrow(), vec!["aa".to_string(), "bb".to_string()]);
+
+    assert_eq!(
+        system.drain_output(&output).unwrap(),
+        vec!["aa".to_string(), "bb".to_string()]
+    );
+    assert!(system.drain_output(&output).unwrap().is_empty());
+
+    system.run_on(input.clone(), &"c".to_string()).unwrap();
+    assert_eq!(system.drain_output(&output).unwrap(), vec!["cc".to_string()]);
+    assert_eq!(events.borrow().len(), 3);
+
+    // only the latest events are kept for draining
+    system.set_output_capacity(&output, 2).unwrap();
+    for event in ["d", "e", "f"] {
+        system.run_on(input.clone(), &event.to_string()).unwrap();
+    }
+    assert_eq!(
+        system.drain_output(&output).unwrap(),
+        vec!["ee".to_string(), "ff".to_string()]
+    );
+    assert_eq!(events.borrow().len(), 6);
+    system.set_output_capacity(&output, 0).unwrap();
+    system.run_on(input.clone(), &"g".to_string()).unwrap();
+    assert!(system.drain_output(&output).unwrap().is_empty());
+    assert_eq!(system.last_output(&output).unwrap(), Some("gg".to_string()));
+    assert_eq!(events.borrow().len(), 7);
+    system.set_output_capacity(&output, 2).unwrap();
+
+    system.disconnect_input(output.handle()).unwrap();
+    system.run_on(input, &"h".to_string()).unwrap();
+    assert!(system.drain_output(&output).unwrap().is_empty());
+
+    // handles of other systems are rejected
+    let foreign = System::default().create_output::<String>();
+    assert!(matches!(system.last_output(&foreign), Err(Error::ForeignHandle)));
+}
+
+#[test]
+fn graph_export() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+    let output = system.create_output::<String>();
+
+    let dup1 = system.add_behaviour(StringDublicator).unwrap();
+    let dup2 = system.add_behaviour(StringDublicator).unwrap();
+    system.connect(&input, &dup2.input).unwrap();
+    system.connect(&dup2.output, &dup1.input).unwrap();
+    system.connect(&dup1.output, &output).unwrap();
+    system.connect_delayed(&dup1.output, &dup2.input).unwrap();
+
+    let graph = system.get_graph(GraphDetail::Nodes);
+    let labels = graph
+        .nodes
+        .iter()
+        .map(|node| node.data.label.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        vec!["input 0", "StringDublicator", "StringDublicator", "output 0"]
+    );
+    // behaviours are listed in the execution order
+    assert_eq!(graph.nodes[1].data.kind, GraphNodeKind::Behaviour(dup2.id()));
+    assert_eq!(graph.nodes[2].data.kind, GraphNodeKind::Behaviour(dup1.id()));
+    let edges = graph
+        .edges
+        .iter()
+        .map(|edge| (edge.from, edge.to, edge.data.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        edges,
+        vec![
+            (0, 1, GraphEdgeKind::Connection),
+            (1, 2, GraphEdgeKind::Connection),
+            (2, 3, GraphEdgeKind::Connection),
+            (2, 1, GraphEdgeKind::Delayed),
+        ]
+    );
+
+    let graph = system.get_graph(GraphDetail::Pins);
+    assert_eq!(graph.nodes.len(), 8);
+    assert_eq!(graph.nodes[2].data.kind, GraphNodeKind::InputPin(dup2.id(), 0));
+    assert_eq!(graph.nodes[2].data.label, "in 0");
+    assert_eq!(
+        graph
+            .edges
+            .iter()
+            .filter(|edge| edge.data.kind == GraphEdgeKind::Pin)
+            .count(),
+        4
+    );
+
+    let dot = system.get_dot_graph(GraphDetail::Whole);
+    assert!(dot.contains("label=\"in 0: String\""));
+    assert!(dot.contains("label=\"delayed: String\""));
+}
+
+#[test]
+fn description() {
+    let mut registry = BehaviourRegistry::default();
+    registry
+        .add_behaviour::<StringDublicator>("StringDublicator")
+        .unwrap()
+        .add_event::<String>("String")
+        .unwrap();
+    assert!(matches!(
+        registry.add_behaviour::<StringDublicator>("StringDublicator"),
+        Err(Error::DuplicateRegistration(_))
+    ));
+    assert!(matches!(
+        registry.add_event::<String>("Text"),
+        Err(Error::DuplicateRegistration(_))
+    ));
+
+    let description = SystemDescription::from_ron(
+        r#"(
+            inputs: [(name: "input", event_type: "String")],
+            outputs: [(name: "output", event_type: "String")],
+            behaviours: [
+                (name: "dup1", behaviour: "StringDublicator"),
+                (name: "dup2", behaviour: "StringDublicator"),
+            ],
+            connections: [
+                (from: System("input"), to: Behaviour("dup1", 0)),
+                (from: Behaviour("dup1", 0), to: Behaviour("dup2", 0)),
+                (from: Behaviour("dup2", 0), to: System("output")),
+                (from: Behaviour("dup2", 0), to: System("output"), delayed: true),
+            ],
+        )"#,
+    )
+    .unwrap();
+
+    let mut system = System::load(&description, &registry).unwrap();
+    let input = TypedOutHandle::<String>::try_from(system.find_input("input").unwrap()).unwrap();
+    let output = TypedInHandle::<String>::try_from(system.find_output("output").unwrap()).unwrap();
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+    assert_eq!(system.drain_output(&output).unwrap(), vec!["aaaa".to_string()]);
+    system.run_on(input, &"b".to_string()).unwrap();
+    assert_eq!(
+        system.drain_output(&output).unwrap(),
+        vec!["aaaa".to_string(), "bbbb".to_string()]
+    );
+
+    let saved = system.save(&registry).unwrap();
+    assert_eq!(saved, description);
+    assert_eq!(
+        SystemDescription::from_ron(&saved.to_ron().unwrap()).unwrap(),
+        description
+    );
+
+    // behaviours added without the registry cannot be saved
+    system.add_behaviour(StringDublicator).unwrap();
+    assert!(matches!(system.save(&registry), Err(Error::UnknownBehaviour(_))));
+
+    let mut unknown = description.clone();
+    unknown.behaviours[0].behaviour = "Unknown".to_string();
+    assert!(matches!(
+        System::load(&unknown, &registry),
+        Err(Error::UnknownBehaviour(_))
+    ));
+
+    // the name of a removed behaviour can be reused
+    let dup1 = system.find_behaviour("dup1").unwrap();
+    assert!(system
+        .add_described_behaviour(&registry, description.behaviours[0].clone())
+        .is_err());
+    system.remove_behaviour(dup1).unwrap();
+    assert_eq!(system.find_behaviour("dup1"), None);
+    let dup1 = system
+        .add_described_behaviour(&registry, description.behaviours[0].clone())
+        .unwrap();
+    assert_eq!(system.find_behaviour("dup1"), Some(dup1));
+
+    // an unnamed input cannot be saved by the name given to another input
+    let mut named = description.clone();
+    named.inputs[0].name = "input_1".to_string();
+    named.connections[0].from = PinReference::System("input_1".to_string());
+    let mut system = System::load(&named, &registry).unwrap();
+    system.create_input::<String>();
+    assert!(matches!(system.save(&registry), Err(Error::InvalidDescription(_))));
+}
+
+#[test]
+fn stats() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+    let output = system.create_output::<String>();
+
+    let dup1 = system.add_behaviour(StringDublicator).unwrap();
+    let dup2 = system.add_behaviour(StringDublicator).unwrap();
+    system.connect(&input, &dup1.input).unwrap();
+    system.connect(&dup1.output, &dup2.input).unwrap();
+    system.connect(&dup1.output, &output).unwrap();
+    system.connect_delayed(&dup2.output, &dup1.input).unwrap();
+
+    assert!(system.stats().is_none());
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+
+    // events before enabling the statistics are not counted
+    system.enable_stats(true);
+    system.run_on(input.clone(), &"b".to_string()).unwrap();
+    system.run_on(input.clone(), &"c".to_string()).unwrap();
+
+    let stats = system.stats().unwrap();
+    assert_eq!(stats.runs, 2);
+    assert_eq!(stats.nodes.len(), 2);
+    let (id, node) = &stats.nodes[0];
+    assert_eq!(*id, dup1.id());
+    assert_eq!(node.name, "StringDublicator");
+    assert_eq!(node.calls, 2);
+    // a system input event and a delayed event in each run
+    assert_eq!(node.received, vec![4]);
+    assert_eq!(node.emitted, vec![2]);
+    let (id, node) = &stats.nodes[1];
+    assert_eq!(*id, dup2.id());
+    assert_eq!(node.calls, 2);
+    assert_eq!(node.received, vec![2]);
+    assert_eq!(node.emitted, vec![2]);
+
+    let csv = stats.to_csv();
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.lines().nth(1).unwrap().contains(",StringDublicator,2,"));
+    assert!(csv.lines().nth(1).unwrap().ends_with(",4,2"));
+    assert_eq!(stats.to_folded().lines().count(), 3);
+
+    // the names with separators or quotes are quoted
+    let mut generic = stats.clone();
+    generic.nodes[0].1.name = "Map<A, \"B\">".to_string();
+    assert!(generic
+        .to_csv()
+        .lines()
+        .nth(1)
+        .unwrap()
+        .contains(",\"Map<A, \"\"B\"\">\",2,"));
+
+    system.reset_stats();
+    assert_eq!(system.stats().unwrap().runs, 0);
+    assert_eq!(system.stats().unwrap().nodes[0].1.calls, 0);
+    system.enable_stats(false);
+    assert!(system.stats().is_none());
+}
+
+#[test]
+fn tracer() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+
+    let dup1 = system.add_behaviour(StringDublicator).unwrap();
+    let dup2 = system.add_behaviour(StringDublicator).unwrap();
+    system.connect(&input, &dup1.input).unwrap();
+    system.connect(&dup1.output, &dup2.input).unwrap();
+
+    let deliveries = Rc::new(RefCell::new(Vec::new()));
+    {
+        let deliveries = deliveries.clone();
+        system.set_tracer(move |delivery: &Delivery| {
+            deliveries.borrow_mut().push((
+                delivery.source_pin,
+                delivery.target_pin,
+                delivery.event_type_name,
+                delivery.debug(),
+            ))
+        });
+    }
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+    assert_eq!(
+        *deliveries.borrow(),
+        vec![
+            (0, 0, "alloc::string::String", None),
+            (0, 0, "alloc::string::String", None)
+        ]
+    );
+    deliveries.borrow_mut().clear();
+
+    // outputs created after the tracer are also traced
+    system.trace_debug::<String>();
+    let recorder = system.add_behaviour(StringDublicator).unwrap();
+    system.connect(&dup2.output, &recorder.input).unwrap();
+    system.run_on(input.clone(), &"b".to_string()).unwrap();
+    let debug = deliveries
+        .borrow()
+        .iter()
+        .map(|d| d.3.clone().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(debug, vec!["\"b\"", "\"bb\"", "\"bbbb\""]);
+    deliveries.borrow_mut().clear();
+
+    // the delayed deliveries are traced to the target when they are forwarded in the next run
+    let targets = Rc::new(RefCell::new(Vec::new()));
+    {
+        let targets = targets.clone();
+        system.set_tracer(move |delivery: &Delivery| {
+            targets.borrow_mut().push((delivery.source_set, delivery.target_set))
+        });
+    }
+    let late = system.add_behaviour(StringDublicator).unwrap();
+    system.connect_delayed(&dup2.output, &late.input).unwrap();
+    system.run_on(input.clone(), &"c".to_string()).unwrap();
+    assert_eq!(targets.borrow().len(), 3);
+    targets.borrow_mut().clear();
+    system.run_on(input.clone(), &"d".to_string()).unwrap();
+    assert_eq!(targets.borrow().len(), 4);
+    assert_eq!(
+        targets.borrow()[0],
+        (dup2.output.set_id(), late.input.handle().set_id())
+    );
+
+    targets.borrow_mut().clear();
+
+    system.remove_tracer();
+    system.run_on(input.clone(), &"e".to_string()).unwrap();
+    system.run_on(input, &"f".to_string()).unwrap();
+    assert!(targets.borrow().is_empty());
+    assert!(deliveries.borrow().is_empty());
+}
+
+#[test]
+fn error_policy() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+    let output = system.create_output::<String>();
+
+    // the failing node comes first in the execution order
+    let dup = system.add_behaviour(StringDublicator).unwrap();
+    let non_empty = system.add_behaviour(NonEmpty).unwrap();
+    system.connect(&input, &non_empty.input).unwrap();
+    system.connect(&input, &dup.input).unwrap();
+    system.connect(&non_empty.output, &dup.input).unwrap();
+    system.connect(&dup.output, &output).unwrap();
+
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+    assert_eq!(system.drain_output(&output).unwrap(), vec!["aa".to_string()]);
+
+    // abort stops the run with the identity of the failed node
+    match system.run_on(input.clone(), &String::new()) {
+        Err(Error::Behaviour(error)) => {
+            assert_eq!(error.node, non_empty.id());
+            assert_eq!(error.name, "NonEmpty");
+            assert_eq!(error.message, "empty input");
+        }
+        _ => panic!("the failure shall abort the run"),
+    }
+    assert!(system.drain_output(&output).unwrap().is_empty());
+
+    // skip continues the run without the failed node
+    system.set_error_policy(ErrorPolicy::Skip).unwrap();
+    system.run_on(input.clone(), &String::new()).unwrap();
+    assert_eq!(system.drain_output(&output).unwrap(), vec![String::new()]);
+
+    // route sends the error to an output
+    let errors = system.create_output::<NodeError>();
+    system.set_error_policy(ErrorPolicy::Route(errors.clone())).unwrap();
+    system.run_on(input, &String::new()).unwrap();
+    assert_eq!(system.drain_output(&output).unwrap(), vec![String::new()]);
+    let errors = system.drain_output(&errors).unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].node, non_empty.id());
+
+    // the errors routed back to a preceding node are delivered in the next run
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+    let output = system.create_output::<String>();
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let retry = system.add_behaviour(Retry(calls.clone())).unwrap();
+    let non_empty = system.add_behaviour(NonEmpty).unwrap();
+    system.connect(&input, &retry.input).unwrap();
+    system.connect(&retry.output, &non_empty.input).unwrap();
+    system.connect(&non_empty.output, &output).unwrap();
+    system
+        .set_error_policy(ErrorPolicy::Route(retry.errors.clone()))
+        .unwrap();
+
+    system.run_on(input.clone(), &String::new()).unwrap();
+    assert_eq!(*calls.borrow(), vec![String::new()]);
+    assert!(system.drain_output(&output).unwrap().is_empty());
+    system.run_on(input, &String::new()).unwrap();
+    assert_eq!(*calls.borrow(), vec![String::new(), "fallback".to_string()]);
+    assert_eq!(system.drain_output(&output).unwrap(), vec!["fallback".to_string()]);
+
+    // the errors can be passed to other threads
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Error>();
+}
+
+#[test]
+fn panic_isolation() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+    let output = system.create_output::<String>();
+
+    let panicking = system.add_behaviour(Panicking).unwrap();
+    let dup = system.add_behaviour(StringDublicator).unwrap();
+    system.connect(&input, &panicking.input).unwrap();
+    system.connect(&panicking.output, &dup.input).unwrap();
+    system.connect(&input, &dup.input).unwrap();
+    system.connect(&dup.output, &output).unwrap();
+    system.isolate_panics(true);
+
+    // the events sent before the panic are delivered and the rest of the graph keeps running
+    system.run_on(input.clone(), &String::new()).unwrap();
+    assert_eq!(system.drain_output(&output).unwrap(), vec![String::new()]);
+    assert_eq!(system.quarantined().len(), 1);
+    assert_eq!(system.quarantined()[0].node, panicking.id());
+    assert_eq!(system.quarantined()[0].message.as_deref(), Some("empty input"));
+
+    // the quarantined node is not executed
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+    assert_eq!(system.drain_output(&output).unwrap(), vec!["aa".to_string()]);
+
+    system.release(panicking.id()).unwrap();
+    assert!(system.quarantined().is_empty());
+    system.run_on(input, &"b".to_string()).unwrap();
+    assert_eq!(system.drain_output(&output).unwrap(), vec!["bb".to_string()]);
+}
+
+#[test]
+fn dangling_connections() {
+    // the connections to the dropped input sets are removed by the next send
+    let mut output_set = FixedOutSet::<Out<String>>::default();
+    let input_set = Rc::new(RefCell::new(FixedInputSet::<StoreLast<String>>::default()));
+    let handle = TypedInHandle::<String>::new(&input_set, 0);
+    output_set.connect(0, handle.handle().clone()).unwrap();
+    output_set.send(&"a".to_string());
+    assert_eq!(input_set.borrow().get().as_str(), "a");
+    drop(input_set);
+    assert_eq!(output_set.pins()[0].connections.len(), 1);
+    output_set.send(&"b".to_string());
+    assert!(output_set.pins()[0].connections.is_empty());
+
+    // the error route to a dropped input set is reported
+    let mut system = System::default();
+    let dup = system.add_behaviour(StringDublicator).unwrap();
+    let non_empty = system.add_behaviour(NonEmpty).unwrap();
+    system.connect(&non_empty.output, &dup.input).unwrap();
+    let errors = Rc::new(RefCell::new(FixedInputSet::<StoreLast<NodeError>>::default()));
+    system
+        .set_error_policy(ErrorPolicy::Route(TypedInHandle::new(&errors, 0)))
+        .unwrap();
+    assert!(system.validate().is_empty());
+    drop(errors);
+    assert!(matches!(
+        system.validate()[..],
+        [Dangling::ErrorRoute { target_pin: 0, .. }]
+    ));
+}
+
+#[test]
+fn foreign_handles() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+    let dup = system.add_behaviour(StringDublicator).unwrap();
+    assert_eq!(input.handle().system_id(), Some(system.id()));
+    assert_eq!(dup.input.handle().system_id(), Some(system.id()));
+
+    let mut other = System::default();
+    let other_output = other.create_output::<String>();
+    let other_dup = other.add_behaviour(StringDublicator).unwrap();
+    assert_ne!(system.id(), other.id());
+
+    // the pins and nodes of another system are rejected
+    assert!(matches!(
+        system.connect(&input, &other_dup.input),
+        Err(Error::ForeignHandle)
+    ));
+    assert!(matches!(
+        system.connect(&dup.output, &other_output),
+        Err(Error::ForeignHandle)
+    ));
+    assert!(matches!(
+        system.connect_delayed(&dup.output, &other_output),
+        Err(Error::ForeignHandle)
+    ));
+    assert!(matches!(
+        system.run_on(other.create_input(), &"a".to_string()),
+        Err(Error::ForeignHandle)
+    ));
+    assert!(matches!(
+        system.remove_behaviour(other_dup.id()),
+        Err(Error::ForeignHandle)
+    ));
+    assert!(matches!(
+        system.set_error_policy(ErrorPolicy::Route(other.create_output())),
+        Err(Error::ForeignHandle)
+    ));
+    assert!(matches!(system.input_pin(other_dup.id(), 0), Err(Error::ForeignHandle)));
+    assert!(system.remove_behaviour(dup.id()).is_ok());
+
+    // the sets not added to any system have no identity
+    let input_set = Rc::new(RefCell::new(FixedInputSet::<StoreLast<String>>::default()));
+    assert_eq!(TypedInHandle::<String>::new(&input_set, 0).handle().system_id(), None);
+}
+
+#[test]
+fn fallible_pins() {
+    let mut system = System::default();
+    let dup = system.add_behaviour(StringDublicator).unwrap();
+
+    // the conversions of the type erased handles are checked
+    let pin_out = system.output_pin(dup.id(), 0).unwrap();
+    assert!(TypedOutHandle::<String>::try_from(pin_out.clone()).is_ok());
+    match TypedOutHandle::<u32>::try_from(pin_out) {
+        Err(Error::TypeMismatch { expected, found }) => {
+            assert_eq!(expected, "u32");
+            assert_eq!(found, "alloc::string::String");
+        }
+        _ => panic!("the conversion shall fail"),
+    }
+
+    // the mismatching pins are reported with their identity and event type
+    let counter = system.create_input::<u32>();
+    match system.connect_any(counter.handle(), dup.input.handle()) {
+        Err(Error::IncompatiblePinTypes { output, input }) => {
+            assert_eq!(output, counter.handle().pin_info());
+            assert_eq!(output.event_type_name, "u32");
+            assert_eq!(input.set, SetId::Input(dup.input.handle().set_id()));
+            assert_eq!(input.pin_id, 0);
+            assert_eq!(input.event_type_name, dup.input.handle().event_type_name());
+        }
+        _ => panic!("the connection shall fail"),
+    }
+
+    // the invalid pin ids are reported
+    let mut output_set = FixedOutSet::<Out<String>>::default();
+    let input_set = Rc::new(RefCell::new(FixedInputSet::<StoreLast<String>>::default()));
+    let handle = TypedInHandle::<String>::new(&input_set, 0);
+    assert!(matches!(
+        output_set.connect(1, handle.handle().clone()),
+        Err(Error::PinNotFound { index: 1, .. })
+    ));
+    assert!(matches!(
+        input_set.borrow_mut().push(1, &String::new()),
+        Err(Error::PinNotFound { index: 1, .. })
+    ));
+    assert!(matches!(
+        input_set.borrow_mut().push(0, &0u32),
+        Err(Error::UnexpectedEventType { .. })
+    ));
+
+    // the input pins are validated when they are connected
+    let missing = TypedInHandle::<String>::new(&input_set, 7);
+    assert!(matches!(
+        output_set.connect(0, missing.handle().clone()),
+        Err(Error::PinNotFound { index: 7, .. })
+    ));
+    let numbers = Rc::new(RefCell::new(FixedInputSet::<StoreLast<u32>>::default()));
+    let mistyped = TypedInHandle::<String>::new(&numbers, 0);
+    match output_set.connect(0, mistyped.handle().clone()) {
+        Err(Error::TypeMismatch { expected, found }) => {
+            assert_eq!(expected, "u32");
+            assert_eq!(found, "alloc::string::String");
+        }
+        _ => panic!("the connection shall fail"),
+    }
+    assert!(output_set.pins()[0].connections.is_empty());
+    let errors = TypedInHandle::<NodeError>::new(&input_set, 0);
+    assert!(matches!(
+        system.set_error_policy(ErrorPolicy::Route(errors)),
+        Err(Error::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn dynamic_input_set() {
+    let mut input_set = DynamicInputSet::default();
+    input_set.add(StoreLast::<String>::default());
+    input_set.add(Unbounded::<u32>::default());
+    let pins = input_set.pins();
+    assert_eq!(pins[0].event_type_id, TypeId::of::<String>());
+    assert_eq!(pins[1].event_type_id, TypeId::of::<u32>());
+
+    // the handles are checked against the inputs
+    let input_set = Rc::new(RefCell::new(input_set));
+    let handle = DynamicInputSet::handle::<String>(&input_set, 0).unwrap();
+    assert!(matches!(
+        DynamicInputSet::handle::<u32>(&input_set, 0),
+        Err(Error::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        DynamicInputSet::handle::<u32>(&input_set, 2),
+        Err(Error::PinNotFound { index: 2, .. })
+    ));
+
+    let mut output_set = FixedOutSet::<Out<String>>::default();
+    output_set.connect(0, handle.handle().clone()).unwrap();
+    output_set.send(&"a".to_string());
+    let input_set = input_set.borrow();
+    assert_eq!(input_set.get::<StoreLast<String>>(0).unwrap().get(), "a");
+    assert!(input_set.get::<Unbounded<u32>>(0).is_none());
+    assert!(input_set.get::<Unbounded<u32>>(1).unwrap().is_empty());
+}
+
+#[test]
+fn bounded_input() {
+    let capacity = NonZeroUsize::new(2).unwrap();
+    let mut drop_oldest = Bounded::<u32>::new(capacity, OverflowPolicy::DropOldest);
+    let mut drop_newest = Bounded::<u32>::new(capacity, OverflowPolicy::DropNewest);
+    let mut flag = Bounded::<u32>::new(capacity, OverflowPolicy::Flag);
+    for event in 0..3 {
+        let changed = event < 2;
+        assert!(drop_oldest.push(&event));
+        assert_eq!(drop_newest.push(&event), changed);
+        assert!(flag.push(&event));
+    }
+
+    assert_eq!(drop_oldest.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(drop_newest.iter().copied().collect::<Vec<_>>(), vec![0, 1]);
+    assert_eq!(flag.iter().copied().collect::<Vec<_>>(), vec![0, 1]);
+    assert!(flag.overflowed());
+    assert_eq!(flag.dropped(), 1);
+
+    // draining makes room and clears the overflow
+    assert_eq!(flag.drain().collect::<Vec<_>>(), vec![0, 1]);
+    assert!(!flag.overflowed());
+    assert!(flag.push(&3));
+    assert_eq!(flag.len(), 1);
+    assert_eq!(flag.capacity(), capacity.get());
+}
+
+#[test]
+fn store_if_changed() {
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+    let output = system.create_output::<String>();
+    let distinct = system.add_behaviour(Distinct).unwrap();
+    let dup = system.add_behaviour(StringDublicator).unwrap();
+    system.connect(&input, &distinct.input).unwrap();
+    system.connect(&distinct.output, &dup.input).unwrap();
+    system.connect(&dup.output, &output).unwrap();
+    system.enable_stats(true);
+
+    // the repeated value is not propagated
+    for event in ["a", "a", "b"] {
+        system.run_on(input.clone(), &event.to_string()).unwrap();
+    }
+    assert_eq!(
+        system.drain_output(&output).unwrap(),
+        vec!["aa".to_string(), "bb".to_string()]
+    );
+    let stats = system.stats().unwrap();
+    assert_eq!(stats.nodes[0].1.calls, 2);
+    assert_eq!(stats.nodes[1].1.calls, 2);
+
+    let mut approx = StoreIfChangedApprox::new(0.1);
+    assert!(approx.push(&1.0));
+    assert!(!approx.push(&1.05));
+    assert!(!approx.push(&1.08));
+    assert!(approx.push(&1.15));
+    assert_eq!(approx.get(), 1.15);
+}
+
+#[test]
+fn changed_inputs() {
+    let mut system = System::default();
+    let first = system.create_input::<String>();
+    let second = system.create_input::<String>();
+    let masks = Rc::new(RefCell::new(Vec::new()));
+    let recorder = system.add_behaviour(ChangeRecorder(masks.clone())).unwrap();
+    system.connect(&first, &recorder.first).unwrap();
+    system.connect(&second, &recorder.second).unwrap();
+
+    system.run_on(first.clone(), &"a".to_string()).unwrap();
+    system.run_on(second.clone(), &"b".to_string()).unwrap();
+    let mut transaction = Transaction::default();
+    transaction.send(&first, "c".to_string()).send(&second, "d".to_string());
+    system.commit(transaction).unwrap();
+    assert_eq!(*masks.borrow(), vec![0b01, 0b10, 0b11]);
+
+    let mut input_set = DynamicInputSet::default();
+    input_set.add(StoreLast::<String>::default());
+    input_set.add(StoreIfChanged::<String>::default());
+    input_set.push(1, &"a".to_string()).unwrap();
+    assert!(!input_set.changed(0));
+    assert!(input_set.changed(1));
+    input_set.reset_dirty();
+    input_set.push(1, &"a".to_string()).unwrap();
+    assert!(!input_set.is_dirty());
+}
+
+#[test]
+fn firing_policy() {
+    let mut system = System::default();
+    let first = system.create_input::<String>();
+    let second = system.create_input::<String>();
+    let all_ready = system.add_behaviour(Join(FiringPolicy::AllReady)).unwrap();
+    let triggered = system.add_behaviour(Join(FiringPolicy::Triggers(vec![0]))).unwrap();
+    let all_ready_events = Rc::new(RefCell::new(Vec::new()));
+    let triggered_events = Rc::new(RefCell::new(Vec::new()));
+    for (join, events) in [(&all_ready, &all_ready_events), (&triggered, &triggered_events)] {
+        system.connect(&first, &join.first).unwrap();
+        system.connect(&second, &join.second).unwrap();
+        let output = system.create_output::<String>();
+        system.connect(&join.output, &output).unwrap();
+        let events = events.clone();
+        system
+            .on_output(&output, move |event: &String| events.borrow_mut().push(event.clone()))
+            .unwrap();
+    }
+
+    system.run_on(second.clone(), &"x".to_string()).unwrap();
+    assert!(all_ready_events.borrow().is_empty());
+    assert!(triggered_events.borrow().is_empty());
+
+    system.run_on(first.clone(), &"a".to_string()).unwrap();
+    assert_eq!(*all_ready_events.borrow(), vec!["ax".to_string()]);
+    assert_eq!(*triggered_events.borrow(), vec!["ax".to_string()]);
+
+    // the trigger samples the changes of the other inputs
+    system.run_on(second.clone(), &"y".to_string()).unwrap();
+    assert_eq!(*all_ready_events.borrow(), vec!["ax".to_string(), "ay".to_string()]);
+    assert_eq!(*triggered_events.borrow(), vec!["ax".to_string()]);
+    system.run_on(first, &"b".to_string()).unwrap();
+    assert_eq!(*triggered_events.borrow(), vec!["ax".to_string(), "by".to_string()]);
+
+    // the triggers have to be pins of the node
+    assert!(matches!(
+        system.add_behaviour(Join(FiringPolicy::Triggers(vec![0, 2]))),
+        Err(Error::PinNotFound { index: 2, .. })
+    ));
+}
+
+#[test]
+fn sampled_input() {
+    let mut sampled = Sampled::<StoreLast<String>>::default();
+    assert!(!sampled.has_value());
+    assert!(!sampled.push(&"a".to_string()));
+    assert!(sampled.has_value());
+    assert_eq!(sampled.get(), "a");
+
+    let mut system = System::default();
+    let input = system.create_input::<String>();
+    let config = system.create_input::<String>();
+    let sample = system.add_behaviour(Sample).unwrap();
+    let output = system.create_output::<String>();
+    system.connect(&input, &sample.input).unwrap();
+    system.connect(&config, &sample.sampled).unwrap();
+    system.connect(&sample.output, &output).unwrap();
+    let events = Rc::new(RefCell::new(Vec::new()));
+    {
+        let events = events.clone();
+        system
+            .on_output(&output, move |event: &String| events.borrow_mut().push(event.clone()))
+            .unwrap();
+    }
+
+    system.run_on(config.clone(), &"x".to_string()).unwrap();
+    assert!(events.borrow().is_empty());
+    system.run_on(input.clone(), &"a".to_string()).unwrap();
+    system.run_on(config, &"y".to_string()).unwrap();
+    system.run_on(input, &"b".to_string()).unwrap();
+    assert_eq!(*events.borrow(), vec!["ax".to_string(), "by".to_string()]);
+}
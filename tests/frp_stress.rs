@@ -4,13 +4,12 @@ extern crate test;
 use rand::Rng;
 use frp::{
     frp::{
-        inputs::StoreLast, Behaviour, BehaviourNode, Error, FixedInputSet, FixedOutSet, IntoBehaviourNode, Out, System,
-        TypedInHandle, TypedOutHandle,
+        inputs::StoreLast, Behaviour, BehaviourError, BehaviourNode, Error, FixedInputSet, FixedOutSet, GraphDetail,
+        IntoBehaviourNode, Out, System, TypedInHandle, TypedOutHandle,
     },
-    graph::{Node, Edge, Graph, DotAttribute}
 };
 use wasmer::{Store, Module, Instance, Value, imports};
-use std::{cell::RefCell, rc::Rc, borrow::Cow};
+use std::{cell::RefCell, rc::Rc};
 use test::Bencher;
 
 pub struct PinLayout {
@@ -19,24 +18,6 @@ pub struct PinLayout {
     pub output: TypedOutHandle<f64>,
 }
 
-struct NodeData(String);
-impl DotAttribute for NodeData {
-    fn label(&self) -> Option<Cow<'_, str>> {
-        Some(Cow::Borrowed(&self.0))
-    }
-}
-
-struct EdgeData(String);
-impl DotAttribute for EdgeData {
-    fn label(&self) -> Option<Cow<'_, str>> {
-        Some(Cow::Borrowed(&self.0))
-    }
-
-    fn font_size(&self) -> Option<u32> {
-        Some(8)
-    }
-}
-
 #[derive(Clone, Copy, Debug)]
 pub enum Op {
     Add,
@@ -103,7 +84,7 @@ impl IntoBehaviourNode for Operation {
     fn into_behaviour_node(self) -> Result<BehaviourNode<Self::Behaviour>, Error> {
         let input_set = FixedInputSet::default();
         let output_set = FixedOutSet::default();
-        Ok(BehaviourNode::new(input_set, output_set, self))
+        BehaviourNode::new(input_set, output_set, self)
     }
 }
 
@@ -128,7 +109,11 @@ impl Behaviour for WasmScript {
     type OutputSet = FixedOutSet<Out<f64>>;
     type PinLayout = PinLayout;
 
-    fn behave(&mut self, input_set: &mut Self::InputSet, output_set: &mut Self::OutputSet) {
+    fn try_behave(
+        &mut self,
+        input_set: &mut Self::InputSet,
+        output_set: &mut Self::OutputSet,
+    ) -> Result<(), BehaviourError> {
         let mut rng = rand::thread_rng();
         let (i1, i2) = &**input_set;
         let i1 = i1.try_get().cloned().unwrap_or_else(|| rng.gen_range(0.0..100.0));
@@ -137,13 +122,14 @@ impl Behaviour for WasmScript {
 
         let p = i1+i2;
         
-        let behave = self.instance.exports.get_function("add_one").unwrap();
-        let result = behave.call(&[Value::F64(p)]).unwrap();
+        let behave = self.instance.exports.get_function("add_one")?;
+        let result = behave.call(&[Value::F64(p)])?;
         let value = match &result[0] {
             Value::F64(p) => *p,
             _ => -1.,
         };
         output.send(&value);
+        Ok(())
     }
 
     fn get_pins(
@@ -166,7 +152,7 @@ impl IntoBehaviourNode for WasmScript {
     fn into_behaviour_node(self) -> Result<BehaviourNode<Self::Behaviour>, Error> {
         let input_set = FixedInputSet::default();
         let output_set = FixedOutSet::default();
-        Ok(BehaviourNode::new(input_set, output_set, self))
+        BehaviourNode::new(input_set, output_set, self)
     }
 }
 
@@ -178,15 +164,12 @@ fn bench_run(b: &mut Bencher) {
     let mut system = System::default();
     let input = system.create_input::<f64>();
 
-    let mut gnodes = Vec::new();
-    let mut gedges = Vec::new();
-
     let mut script_node = 0;
     let mut rng = rand::thread_rng();
     let mut nodes = Vec::new();
     let mut connected = Vec::new();
     for _ in 0..NODES {
-        let (name, pin_layout) = if rng.gen_bool(0.7) {
+        let pin_layout = if rng.gen_bool(0.7) {
             let module_wat = r#"
             (module
               (type $t0 (func (param f64) (result f64)))
@@ -197,7 +180,7 @@ fn bench_run(b: &mut Bencher) {
             "#;
 
             script_node += 1;
-            ("wasm".to_string(), system.add_behaviour(WasmScript::new(module_wat)).unwrap())
+            system.add_behaviour(WasmScript::new(module_wat)).unwrap()
         }
         else {
             let op = match rng.gen_range(0u8..6) {
@@ -209,15 +192,11 @@ fn bench_run(b: &mut Bencher) {
                 _ => Op::Avg,
             };
 
-            (format!("{:?}", op), system.add_behaviour(Operation(op)).unwrap())
+            system.add_behaviour(Operation(op)).unwrap()
         };
         nodes.push(pin_layout);
         connected.push((false,false));
-
-        gnodes.push(Node{data: NodeData(name)});
     }
-    // system input
-    gnodes.push(Node{data: NodeData("INPUT".to_string())});
 
     let mut edge_count = 0;
     for _ in 0..EDGES {
@@ -236,11 +215,6 @@ fn bench_run(b: &mut Bencher) {
                 continue;
             }
             system.connect(&nodes[from].output, &nodes[to].in1).unwrap();
-            gedges.push(Edge{
-                data: EdgeData("P1".to_string()),
-                from,
-                to,
-            });
             connected[to].0 = true;
             edge_count += 1;
         } else {
@@ -248,11 +222,6 @@ fn bench_run(b: &mut Bencher) {
                 continue;
             }
             system.connect(&nodes[from].output, &nodes[to].in2).unwrap();
-            gedges.push(Edge{
-                data: EdgeData("P2".to_string()),
-                from,
-                to,
-            });
             connected[to].1 = true;
             edge_count += 1;
         }
@@ -262,27 +231,16 @@ fn bench_run(b: &mut Bencher) {
     for (n, (a,b)) in connected.into_iter().enumerate() {
         if !a {
             system.connect(&input, &nodes[n].in1).unwrap();
-            gedges.push(Edge{
-                data: EdgeData("P1".to_string()),
-                from: nodes.len(),
-                to: n,
-            });
             incount += 1;
         }
 
         if !b {
             system.connect(&input, &nodes[n].in2).unwrap();
-            gedges.push(Edge{
-                data: EdgeData("P2".to_string()),
-                from: nodes.len(),
-                to: n,
-            });
             incount += 1;
         }
     }
 
-    let ggraph = Graph{nodes: gnodes, edges: gedges};
-    println!("{}", ggraph.dot_graph());    
+    println!("{}", system.get_dot_graph(GraphDetail::Pins));
 
     println!("node: {}", nodes.len());
     println!("script node: {}", script_node);    
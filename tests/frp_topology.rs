@@ -0,0 +1,106 @@
+#![feature(test)]
+extern crate test;
+
+use frp::graph::{Edge, Graph, Node, TopologyOrder};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use test::Bencher;
+
+/// Generate random edges of a DAG. The nodes are ranked by a random permutation and edges point from the
+/// lower rank to the higher rank, thus the insertion order of the nodes is not a valid topology order.
+fn random_dag(rng: &mut StdRng, nodes: usize, edges: usize) -> Vec<(usize, usize)> {
+    let mut rank = (0..nodes).collect::<Vec<_>>();
+    rank.shuffle(rng);
+
+    let mut result = Vec::with_capacity(edges);
+    while result.len() < edges {
+        let a = rng.gen_range(0..nodes);
+        let b = rng.gen_range(0..nodes);
+        if rank[a] < rank[b] {
+            result.push((a, b));
+        } else if rank[b] < rank[a] {
+            result.push((b, a));
+        }
+    }
+    result
+}
+
+fn full_order(nodes: usize, edges: &[(usize, usize)]) -> Option<Vec<usize>> {
+    let graph = Graph {
+        nodes: (0..nodes).map(|_| Node { data: () }).collect(),
+        edges: edges.iter().map(|&(from, to)| Edge { data: (), from, to }).collect(),
+    };
+    graph.get_topology_order()
+}
+
+fn incremental_order(nodes: usize, edges: &[(usize, usize)]) -> TopologyOrder {
+    let mut topology = TopologyOrder::default();
+    for n in 0..nodes {
+        topology.add_node(n);
+    }
+    for &(from, to) in edges {
+        assert!(topology.add_edge(from, to));
+    }
+    topology
+}
+
+const BUILD_NODES: usize = 1000;
+const BUILD_EDGES: usize = 2000;
+
+/// Build a graph edge by edge and recalculate the whole order after each connection.
+#[bench]
+fn bench_build_full_order(b: &mut Bencher) {
+    let mut rng = StdRng::seed_from_u64(1);
+    let edges = random_dag(&mut rng, BUILD_NODES, BUILD_EDGES);
+
+    b.iter(|| {
+        for count in 1..=edges.len() {
+            assert!(full_order(BUILD_NODES, &edges[..count]).is_some());
+        }
+    });
+}
+
+/// Build a graph edge by edge and maintain the order incrementally.
+#[bench]
+fn bench_build_incremental_order(b: &mut Bencher) {
+    let mut rng = StdRng::seed_from_u64(1);
+    let edges = random_dag(&mut rng, BUILD_NODES, BUILD_EDGES);
+
+    b.iter(|| incremental_order(BUILD_NODES, &edges));
+}
+
+const EDIT_NODES: usize = 50000;
+const EDIT_EDGES: usize = 100000;
+const EDIT_CANDIDATES: usize = 1000;
+
+/// Add and remove a single edge in a large graph recalculating the whole order.
+#[bench]
+fn bench_edit_full_order(b: &mut Bencher) {
+    let mut rng = StdRng::seed_from_u64(2);
+    let mut edges = random_dag(&mut rng, EDIT_NODES, EDIT_EDGES + EDIT_CANDIDATES);
+    let candidates = edges.split_off(EDIT_EDGES);
+    let mut candidates = candidates.iter().cycle();
+
+    b.iter(|| {
+        // NO-PANIC: cycle over a non-empty list
+        edges.push(*candidates.next().unwrap());
+        assert!(full_order(EDIT_NODES, &edges).is_some());
+        edges.pop();
+    });
+}
+
+/// Add and remove a single edge in a large graph maintaining the order incrementally.
+#[bench]
+fn bench_edit_incremental_order(b: &mut Bencher) {
+    let mut rng = StdRng::seed_from_u64(2);
+    let mut edges = random_dag(&mut rng, EDIT_NODES, EDIT_EDGES + EDIT_CANDIDATES);
+    let candidates = edges.split_off(EDIT_EDGES);
+    let mut candidates = candidates.iter().cycle();
+    let mut topology = incremental_order(EDIT_NODES, &edges);
+
+    b.iter(|| {
+        // NO-PANIC: cycle over a non-empty list
+        let &(from, to) = candidates.next().unwrap();
+        assert!(topology.add_edge(from, to));
+        assert!(topology.remove_edge(from, to));
+    });
+}
@@ -1,7 +1,12 @@
-use frp::graph::{DotAttribute, Edge, Graph, Node};
+use frp::graph::{DotAttribute, Edge, Graph, Node, TopologyOrder};
+use std::borrow::Cow;
 
 struct NodeData(usize);
-impl DotAttribute for NodeData {}
+impl DotAttribute for NodeData {
+    fn label(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Owned(self.0.to_string()))
+    }
+}
 
 struct EdgeData;
 impl DotAttribute for EdgeData {}
@@ -47,3 +52,91 @@ fn case_3() {
     log::trace!("{}", graph.dot_graph());
     assert_eq!(graph.get_topology_order(), None);
 }
+
+fn assert_order(topology: &TopologyOrder, edges: &[(usize, usize)]) {
+    let order = topology.iter().collect::<Vec<_>>();
+    for (f, t) in edges {
+        let f = order.iter().position(|n| n == f).unwrap();
+        let t = order.iter().position(|n| n == t).unwrap();
+        assert!(f < t, "invalid order: {:?}", order);
+    }
+}
+
+#[test]
+fn incremental_case_1() {
+    let mut topology = TopologyOrder::default();
+    for n in 0..4 {
+        topology.add_node(n);
+    }
+
+    let edges = [(1, 0), (2, 0), (3, 0)];
+    for (f, t) in edges {
+        assert!(topology.add_edge(f, t));
+    }
+    assert_order(&topology, &edges);
+}
+
+#[test]
+fn incremental_case_2() {
+    let mut topology = TopologyOrder::default();
+    for n in 0..6 {
+        topology.add_node(n);
+    }
+
+    let edges = [(5, 0), (4, 0), (4, 1), (1, 3), (2, 3), (5, 2)];
+    for (f, t) in edges {
+        assert!(topology.add_edge(f, t));
+    }
+    assert_order(&topology, &edges);
+}
+
+#[test]
+fn incremental_case_3() {
+    let mut topology = TopologyOrder::default();
+    for n in 0..4 {
+        topology.add_node(n);
+    }
+
+    let edges = [(0, 1), (1, 2), (2, 3)];
+    for (f, t) in edges {
+        assert!(topology.add_edge(f, t));
+    }
+    assert!(!topology.add_edge(3, 1));
+    assert!(!topology.add_edge(2, 2));
+    assert_order(&topology, &edges);
+
+    // the rejected edges shall not alter the graph
+    assert_eq!(topology.successors(3), &[] as &[usize]);
+    assert_eq!(topology.successors(2), &[3]);
+
+    // cycle is allowed once the path is broken
+    assert!(topology.remove_edge(1, 2));
+    assert!(topology.add_edge(3, 1));
+    assert_order(&topology, &[(0, 1), (2, 3), (3, 1)]);
+}
+
+#[test]
+fn incremental_remove_node() {
+    let mut topology = TopologyOrder::default();
+    for n in 0..5 {
+        topology.add_node(n);
+    }
+
+    let edges = [(4, 3), (3, 2), (2, 1), (1, 0)];
+    for (f, t) in edges {
+        assert!(topology.add_edge(f, t));
+    }
+
+    topology.remove_node(2);
+    topology.remove_node(4);
+    topology.remove_node(3);
+    assert!(!topology.contains(2));
+    assert_eq!(topology.predecessors(1), &[] as &[usize]);
+    assert_order(&topology, &[(1, 0)]);
+
+    // index of a removed node can be reused
+    topology.add_node(2);
+    assert!(topology.add_edge(0, 2));
+    assert_order(&topology, &[(1, 0), (0, 2)]);
+    assert_eq!(topology.iter().count(), 3);
+}